@@ -7,9 +7,11 @@ pub mod device_info;
 pub mod key;
 pub mod message;
 pub mod screen;
+pub mod text;
 
 pub use audio::AudioData;
 pub use device_info::*;
 pub use key::{KeyCode, KeyEvent};
 pub use message::Message;
 pub use screen::{FullScreenData, IncrementalScreenData, UpdateRegion};
+pub use text::TextData;
@@ -3,6 +3,7 @@ use crate::{
     audio::AudioData,
     key::KeyEvent,
     screen::{FullScreenData, IncrementalScreenData},
+    text::TextData,
 };
 use serde::{Deserialize, Serialize};
 
@@ -17,4 +18,5 @@ pub enum Message {
     // 控制模块发送的消息
     FullScreenUpdate(FullScreenData),
     IncrementalScreenUpdate(IncrementalScreenData),
+    Text(TextData),
 }
@@ -0,0 +1,10 @@
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+/// 文本消息（服务器 -> 设备）
+/// 用于推送设备所在场景中的助手回复
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextData {
+    /// 文本内容
+    pub text: String,
+}
@@ -6,6 +6,7 @@ use nihility_module_browser_control::BrowserControl;
 use std::collections::HashMap;
 use std::sync::Arc;
 pub(crate) use task::message_handle::start_message_handle;
+pub(crate) use task::reply_forward::start_reply_forward;
 use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
@@ -25,6 +26,8 @@ pub struct Device {
     pub screen_refresh_task: Option<JoinHandle<Result<()>>>,
     pub cancellation_token: CancellationToken,
     pub scene_id_sender: Option<oneshot::Sender<Uuid>>,
+    /// 设备当前连接的场景
+    pub scene_id: Option<Uuid>,
     pub audio_vad_task: Option<
         JoinHandle<core::result::Result<(), nihility_util_vad::error::VoiceActivityDetectionError>>,
     >,
@@ -42,6 +45,7 @@ impl Device {
             screen_refresh_task: None,
             cancellation_token: CancellationToken::new(),
             scene_id_sender: None,
+            scene_id: None,
             audio_vad_task: None,
             audio_handle_task: None,
         }
@@ -1,4 +1,5 @@
 pub mod audio_handle;
 pub mod key_handle;
 pub mod message_handle;
+pub mod reply_forward;
 pub mod screen_refresh;
@@ -1,6 +1,6 @@
 use crate::error::*;
 use nihility_module_message_pool::func::add_message::AddMessagesParam;
-use nihility_module_message_pool::{ContentData, Message, MessagePool};
+use nihility_module_message_pool::{ContentData, Message, MessagePool, Role};
 use nihility_module_model::func::speech_recognition::SpeechRecognitionParam;
 use nihility_module_model::Model;
 use std::path::PathBuf;
@@ -44,6 +44,9 @@ pub async fn start_audio_handle(
                         content: ContentData::Text { body: asr_result },
                         metadata: Default::default(),
                     }],
+                    role: Role::Device,
+                    source: Some(device_id.clone()),
+                    reply_to_group_id: None,
                 })
                 .await?;
         }
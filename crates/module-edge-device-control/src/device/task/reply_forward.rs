@@ -0,0 +1,51 @@
+use crate::device::Device;
+use crate::error::*;
+use nihility_edge_protocol::{Message, TextData};
+use nihility_module_message_pool::{ContentData, SceneEvent};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{RwLock, broadcast};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 将消息池的回复推送给连接到对应场景的设备
+pub(crate) fn start_reply_forward(
+    mut event_receiver: broadcast::Receiver<SceneEvent>,
+    devices: Arc<RwLock<HashMap<String, Device>>>,
+) -> JoinHandle<Result<()>> {
+    tokio::spawn(async move {
+        loop {
+            let reply = match event_receiver.recv().await {
+                Ok(SceneEvent::ReplyCreated(reply)) => reply,
+                Ok(_) => continue,
+                Err(RecvError::Lagged(count)) => {
+                    warn!("Reply forward lagged, {} events dropped", count);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            let ContentData::Text { body } = reply.content else {
+                continue;
+            };
+            for device in devices.read().await.values() {
+                if device.scene_id == Some(reply.scene_id)
+                    && let Some(ws_sender) = &device.ws_sender
+                {
+                    debug!("Forward reply to device {}", device.info.device_id);
+                    if ws_sender
+                        .send(Message::Text(TextData { text: body.clone() }))
+                        .is_err()
+                    {
+                        warn!(
+                            "Failed to forward reply to device {}",
+                            device.info.device_id
+                        );
+                    }
+                }
+            }
+        }
+        info!("Reply forward task exit");
+        Ok(())
+    })
+}
@@ -61,6 +61,7 @@ pub async fn connect_device(
         && scene_id_sender.send(scene_id).is_ok()
     {
         debug!(?scene_id, "send scene id to audio handle");
+        device.scene_id = Some(scene_id);
     } else {
         return Err(EdgeDeviceControlError::DeviceStatus(
             "failed to send scene id to audio handle".to_string(),
@@ -5,7 +5,7 @@ pub mod func;
 use crate::error::*;
 
 use crate::device::register::register_device;
-use crate::device::Device;
+use crate::device::{Device, start_reply_forward};
 use axum::extract::ws::WebSocket;
 use nihility_module_browser_control::BrowserControl;
 use nihility_module_message_pool::MessagePool;
@@ -51,6 +51,7 @@ pub struct EdgeDeviceControl {
     model: Option<Arc<RwLock<Model>>>,
     message_pool: Option<Arc<RwLock<MessagePool>>>,
     web_socket_receive_task: Option<JoinHandle<Result<()>>>,
+    reply_forward_task: Option<JoinHandle<Result<()>>>,
     register_timeout_secs: usize,
     auto_connect: Arc<HashMap<String, AutoConnectDevice>>,
 }
@@ -88,6 +89,7 @@ impl EdgeDeviceControl {
             model: None,
             message_pool: None,
             web_socket_receive_task: None,
+            reply_forward_task: None,
             register_timeout_secs: config.register_timeout_secs,
             auto_connect: Arc::new(auto_connect),
         };
@@ -95,18 +97,24 @@ impl EdgeDeviceControl {
     }
 
     pub async fn start_register_device(&mut self) -> Result<()> {
-        if self.model.is_none() || self.browser_control.is_none() || self.message_pool.is_none() {
+        let (Some(model), Some(message_pool), Some(browser_control)) = (
+            self.model.clone(),
+            self.message_pool.clone(),
+            self.browser_control.clone(),
+        ) else {
             return Err(EdgeDeviceControlError::DeviceStatus(
                 "Module model, message_pool, browser_control is required".to_string(),
             ));
+        };
+        if self.reply_forward_task.is_none() {
+            let event_receiver = message_pool.read().await.subscribe_events();
+            self.reply_forward_task =
+                Some(start_reply_forward(event_receiver, self.devices.clone()));
         }
         let (web_socket_sender, mut web_socket_receiver) = mpsc::unbounded_channel::<WebSocket>();
 
         let web_socket_devices = self.devices.clone();
         let register_timeout_secs = self.register_timeout_secs;
-        let model = self.model.as_ref().unwrap().clone();
-        let message_pool = self.message_pool.as_ref().unwrap().clone();
-        let browser_control = self.browser_control.as_ref().unwrap().clone();
         let auto_connect = self.auto_connect.clone();
         let web_socket_receive_task = tokio::spawn(async move {
             info!("Starting web socket receiver");
@@ -210,6 +218,20 @@ pub async fn monitor_task(module: Arc<RwLock<EdgeDeviceControl>>) {
                 }
             }
         }
+        if let Some(task) = module.reply_forward_task.as_ref()
+            && task.is_finished()
+            && let Some(task) = module.reply_forward_task.take()
+        {
+            match task.await {
+                Ok(Ok(())) => info!("Reply forward task finished"),
+                Ok(Err(e)) => {
+                    error!("Reply forward task failed: {}", e);
+                }
+                Err(join_err) => {
+                    error!("Reply forward task join failed: {}", join_err);
+                }
+            }
+        }
 
         let mut devices = module.devices.write().await;
 
@@ -0,0 +1,266 @@
+use crate::error::*;
+use crate::{CallPermission, ModuleManager, ModuleType};
+use nihility_module_message_pool::ContentData;
+use nihility_module_model::func::text_completion::TextCompletionParam;
+use nihility_store_operate::message::MessageRole;
+use nihility_store_operate::{analysis_result, message};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Write;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+/// 智能体执行轨迹在分析结果中的分析器名称
+pub const AGENT_TRACE_ANALYZER: &str = "agent";
+
+/// 智能体配置
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AgentConfig {
+    /// 单次运行的最大步数
+    #[serde(default = "default_max_steps")]
+    pub max_steps: usize,
+}
+
+fn default_max_steps() -> usize {
+    8
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            max_steps: default_max_steps(),
+        }
+    }
+}
+
+/// 智能体可用工具
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AgentTool {
+    /// 工具所属模块
+    pub module: String,
+    /// 方法名称
+    pub name: String,
+    /// 方法描述
+    pub description: String,
+    /// 参数 JSON Schema
+    pub parameters: Value,
+}
+
+/// 模型返回的工具调用
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// 目标模块
+    pub module: String,
+    /// 目标方法
+    pub function: String,
+    /// 调用参数
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+/// 模型单步输出
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct AgentReply {
+    #[serde(default)]
+    tool_calls: Vec<ToolCall>,
+    #[serde(default)]
+    answer: Option<String>,
+}
+
+/// 工具调用结果
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    /// 工具调用
+    pub call: ToolCall,
+    /// 调用成功时的返回值
+    pub result: Option<Value>,
+    /// 调用失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 单步执行轨迹
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AgentStep {
+    /// 步骤序号，从 1 开始
+    pub step: usize,
+    /// 模型原始输出
+    pub output: String,
+    /// 本步执行的工具调用
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// 最终回答，仅在最后一步存在
+    pub answer: Option<String>,
+}
+
+/// 智能体运行结果
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AgentResult {
+    /// 最终回答
+    pub answer: String,
+    /// 执行步数
+    pub steps: usize,
+}
+
+impl ModuleManager {
+    /// 获取智能体可用的工具列表，仅包含低权限的非流式方法
+    pub async fn agent_tools(&self) -> Vec<AgentTool> {
+        let mut tools = Vec::new();
+        for (module_type, module) in &self.modules {
+            for func in module.read().await.no_perm_func() {
+                if func.tags.iter().any(|tag| tag == "streaming") {
+                    continue;
+                }
+                tools.push(AgentTool {
+                    module: module_type.to_string(),
+                    name: func.name,
+                    description: func.desc,
+                    parameters: func.params,
+                });
+            }
+        }
+        tools
+    }
+
+    /// 以消息组为对话历史运行智能体
+    ///
+    /// 模型返回的工具调用通过 [`ModuleManager::call`] 以低权限执行，
+    /// 每一步的轨迹都会以消息组关联的分析结果保存
+    pub async fn run_agent(&self, group_id: Uuid) -> Result<AgentResult> {
+        let model = self.get_model()?;
+        let mut history = String::new();
+        for msg in message::find_message_by_group_id(&self.conn, group_id).await? {
+            let content: ContentData = serde_json::from_value(msg.content)?;
+            let text = match content {
+                ContentData::Text { body } => body,
+                ContentData::Audio { .. } => "[音频]".to_string(),
+                ContentData::Image { .. } => "[图片]".to_string(),
+                ContentData::Video { .. } => "[视频]".to_string(),
+                ContentData::Summary { body } => format!("[摘要] {}", body),
+            };
+            let speaker = match msg.role {
+                MessageRole::Assistant | MessageRole::Tool => "助手",
+                MessageRole::System => "系统",
+                MessageRole::User | MessageRole::Device => "用户",
+            };
+            let _ = writeln!(history, "{}: {}", speaker, text);
+        }
+        let tools = serde_json::to_string(&self.agent_tools().await)?;
+
+        for step in 1..=self.agent_config.max_steps {
+            let prompt = build_prompt(&tools, &history);
+            let output = model
+                .read()
+                .await
+                .text_completion(&TextCompletionParam { prompt })
+                .await?;
+            debug!("Agent step {} output: {}", step, output);
+            let reply = parse_reply(&output);
+            let _ = writeln!(history, "助手: {}", output.trim());
+
+            let mut records = Vec::with_capacity(reply.tool_calls.len());
+            for call in reply.tool_calls {
+                let record = self.execute_tool_call(call).await;
+                let _ = writeln!(
+                    history,
+                    "工具结果({}.{}): {}",
+                    record.call.module,
+                    record.call.function,
+                    match (&record.result, &record.error) {
+                        (Some(result), _) => result.to_string(),
+                        (None, Some(error)) => format!("调用失败: {}", error),
+                        (None, None) => String::new(),
+                    }
+                );
+                records.push(record);
+            }
+
+            let answer = if records.is_empty() {
+                Some(reply.answer.unwrap_or_else(|| output.trim().to_string()))
+            } else {
+                None
+            };
+            let trace = AgentStep {
+                step,
+                output,
+                tool_calls: records,
+                answer: answer.clone(),
+            };
+            analysis_result::insert_analysis_result(
+                &self.conn,
+                group_id,
+                AGENT_TRACE_ANALYZER,
+                serde_json::to_value(&trace)?,
+            )
+            .await?;
+
+            if let Some(answer) = answer {
+                info!("Agent finished group_id {} in {} steps", group_id, step);
+                return Ok(AgentResult {
+                    answer,
+                    steps: step,
+                });
+            }
+        }
+
+        Err(ModuleManagerError::AgentStepsExceeded(
+            self.agent_config.max_steps,
+        ))
+    }
+
+    /// 执行单个工具调用，失败信息记录在结果中反馈给模型
+    async fn execute_tool_call(&self, call: ToolCall) -> ToolCallRecord {
+        let result = match call.module.parse::<ModuleType>() {
+            Ok(module_type) => self
+                .call(
+                    &module_type,
+                    &call.function,
+                    call.arguments.clone(),
+                    CallPermission::NoPerm,
+                )
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(result) => ToolCallRecord {
+                call,
+                result: Some(result),
+                error: None,
+            },
+            Err(error) => ToolCallRecord {
+                call,
+                result: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+/// 构建智能体提示词
+fn build_prompt(tools: &str, history: &str) -> String {
+    format!(
+        "你是一个可以调用工具的助手，请根据对话历史完成用户的请求。\n\n\
+         可用工具（JSON）：\n{}\n\n\
+         对话历史：\n{}\n\
+         请仅输出一个 JSON 对象：需要调用工具时输出 \
+         {{\"tool_calls\": [{{\"module\": 模块, \"function\": 方法, \"arguments\": 参数对象}}]}}，\
+         可以直接回答时输出 {{\"answer\": 回答内容}}。",
+        tools, history
+    )
+}
+
+/// 解析模型输出，无法解析为 JSON 时视为最终回答
+fn parse_reply(output: &str) -> AgentReply {
+    // 去除推理模型输出的思考内容
+    let output = match output.rfind("</think>") {
+        Some(index) => &output[index + "</think>".len()..],
+        None => output,
+    };
+    let json = match (output.find('{'), output.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &output[start..=end],
+        _ => "",
+    };
+    serde_json::from_str(json).unwrap_or_else(|_| AgentReply {
+        tool_calls: Vec::new(),
+        answer: Some(output.trim().to_string()),
+    })
+}
@@ -20,12 +20,27 @@ pub enum ModuleManagerError {
     #[error(transparent)]
     Scene(#[from] nihility_module_scene_manager::error::SceneError),
 
+    #[error(transparent)]
+    Wasm(#[from] nihility_module_wasm::error::WasmError),
+
+    #[error(transparent)]
+    Store(#[from] nihility_store_operate::error::StoreError),
+
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+
     #[error("Module not found: {0:?}")]
     ModuleNotFound(crate::ModuleType),
 
     #[error("Function not found: {0}")]
     FunctionNotFound(String),
 
+    #[error("Permission denied for function: {0}")]
+    PermissionDenied(String),
+
+    #[error("Agent exceeded max steps: {0}")]
+    AgentStepsExceeded(usize),
+
     #[error(transparent)]
     Anyhow(#[from] anyhow::Error),
 }
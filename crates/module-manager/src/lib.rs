@@ -1,13 +1,19 @@
+pub mod agent;
 pub mod error;
 
+use crate::agent::AgentConfig;
 use crate::error::*;
 use nihility_module::{BoxStream, FunctionMetadata, Module};
 use nihility_module_edge_device_control::EdgeDeviceControl;
-use nihility_module_message_pool::MessagePool;
+use nihility_module_message_pool::{AvailableFunction, MessagePool};
+use nihility_module_model::Model;
+use nihility_module_scene_manager::SceneManager;
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::error;
@@ -38,14 +44,33 @@ pub struct ModuleFunctions {
     pub perm_func: Vec<FunctionMetadata>,
 }
 
+/// 调用者权限
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CallPermission {
+    /// 仅允许调用低权限方法
+    NoPerm,
+    /// 允许调用所有方法
+    Perm,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ModuleManagerConfig {
     pub enable_modules: Vec<ModuleType>,
+    /// 智能体配置
+    #[serde(default)]
+    pub agent: AgentConfig,
 }
 
 pub struct ModuleManager {
+    conn: DatabaseConnection,
     modules: HashMap<ModuleType, Arc<RwLock<dyn Module + Send + Sync>>>,
+    /// 各模块的高权限方法名称，初始化时缓存
+    perm_funcs: HashMap<ModuleType, HashSet<String>>,
     edge_device_control: Option<Arc<RwLock<EdgeDeviceControl>>>,
+    model: Option<Arc<RwLock<Model>>>,
+    message_pool: Option<Arc<RwLock<MessagePool>>>,
+    scene_manager: Option<Arc<RwLock<SceneManager>>>,
+    agent_config: AgentConfig,
 }
 
 impl ModuleManager {
@@ -59,6 +84,7 @@ impl ModuleManager {
 
     pub async fn init(mut config: ModuleManagerConfig, conn: DatabaseConnection) -> Result<Self> {
         config = config.sorted();
+        let agent_config = config.agent.clone();
         let mut modules: HashMap<ModuleType, Arc<RwLock<dyn Module + Send + Sync>>> =
             HashMap::new();
 
@@ -66,6 +92,7 @@ impl ModuleManager {
         let mut edge_device_control = None;
         let mut model = None;
         let mut message_pool: Option<Arc<RwLock<MessagePool>>> = None;
+        let mut scene_manager = None;
 
         for enable_module in config.enable_modules {
             match enable_module {
@@ -81,9 +108,8 @@ impl ModuleManager {
                         modules.insert(ModuleType::Embed(embed_module), module);
                     }
                     EmbedModule::Model => {
-                        let module = Arc::new(RwLock::new(
-                            nihility_module_model::Model::init_from_db_config(conn.clone()).await?,
-                        ));
+                        let module =
+                            Arc::new(RwLock::new(Model::init_from_db_config(conn.clone()).await?));
                         model = Some(module.clone());
                         modules.insert(ModuleType::Embed(embed_module), module);
                     }
@@ -124,12 +150,16 @@ impl ModuleManager {
                         modules.insert(ModuleType::Embed(embed_module), module);
                     }
                     EmbedModule::MessagePool => {
-                        let module = Arc::new(RwLock::new(
-                            nihility_module_message_pool::MessagePool::init_from_db_config(
-                                conn.clone(),
-                            )
-                            .await?,
-                        ));
+                        let mut module = MessagePool::init_from_db_config(conn.clone()).await?;
+                        if let Some(model) = model.as_ref() {
+                            module.set_model(model.clone());
+                        } else {
+                            error!(
+                                "model module does not exist for module type: {:?}",
+                                embed_module
+                            );
+                        }
+                        let module = Arc::new(RwLock::new(module));
                         message_pool = Some(module.clone());
                         let monitor_module = module.clone();
                         tokio::spawn(nihility_module_message_pool::monitor_task(monitor_module));
@@ -137,22 +167,60 @@ impl ModuleManager {
                     }
                     EmbedModule::SceneManager => {
                         let module = Arc::new(RwLock::new(
-                            nihility_module_scene_manager::SceneManager::init_from_db_config(
-                                conn.clone(),
-                            )
-                            .await?,
+                            SceneManager::init_from_db_config(conn.clone()).await?,
                         ));
+                        scene_manager = Some(module.clone());
                         modules.insert(ModuleType::Embed(embed_module), module);
                     }
                 },
                 ModuleType::Wasm(path) => {
-                    error!("wasm module not support yet: {}", path);
+                    let module = Arc::new(RwLock::new(
+                        nihility_module_wasm::WasmModule::init(&path).await?,
+                    ));
+                    modules.insert(ModuleType::Wasm(path), module);
                 }
             }
         }
+        let mut perm_funcs = HashMap::new();
+        let mut available_functions = Vec::new();
+        for (module_type, module) in &modules {
+            let mut module_guard = module.write().await;
+            let names = module_guard
+                .perm_func()
+                .into_iter()
+                .map(|func| func.name)
+                .collect::<HashSet<_>>();
+            perm_funcs.insert(module_type.clone(), names);
+            available_functions.extend(module_guard.no_perm_func().into_iter().map(|metadata| {
+                AvailableFunction {
+                    module: module_type.to_string(),
+                    metadata,
+                }
+            }));
+        }
+
+        // 所有模块加载完成后再启动消息分析，分析器可使用完整的方法列表
+        if let Some(message_pool) = message_pool.as_ref() {
+            let mut message_pool = message_pool.write().await;
+            message_pool.set_available_functions(available_functions);
+            message_pool.set_modules(
+                modules
+                    .iter()
+                    .map(|(module_type, module)| (module_type.to_string(), module.clone()))
+                    .collect(),
+            );
+            message_pool.start_analysis_worker()?;
+        }
+
         Ok(Self {
+            conn,
             modules,
+            perm_funcs,
             edge_device_control,
+            model,
+            message_pool,
+            scene_manager,
+            agent_config,
         })
     }
 
@@ -167,6 +235,27 @@ impl ModuleManager {
             })
     }
 
+    pub fn get_model(&self) -> Result<Arc<RwLock<Model>>> {
+        self.model.as_ref().map(Clone::clone).ok_or_else(|| {
+            ModuleManagerError::ModuleNotFound(ModuleType::Embed(EmbedModule::Model))
+        })
+    }
+
+    pub fn get_message_pool(&self) -> Result<Arc<RwLock<MessagePool>>> {
+        self.message_pool.as_ref().map(Clone::clone).ok_or_else(|| {
+            ModuleManagerError::ModuleNotFound(ModuleType::Embed(EmbedModule::MessagePool))
+        })
+    }
+
+    pub fn get_scene_manager(&self) -> Result<Arc<RwLock<SceneManager>>> {
+        self.scene_manager
+            .as_ref()
+            .map(Clone::clone)
+            .ok_or_else(|| {
+                ModuleManagerError::ModuleNotFound(ModuleType::Embed(EmbedModule::SceneManager))
+            })
+    }
+
     /// 查询所有模块的功能列表
     /// 返回: HashMap<ModuleType, ModuleFunctions>
     pub async fn query_functions(&self) -> HashMap<ModuleType, ModuleFunctions> {
@@ -203,17 +292,37 @@ impl ModuleManager {
         })
     }
 
+    /// 检查调用者是否有权限调用指定方法
+    fn check_permission(
+        &self,
+        module_type: &ModuleType,
+        func_name: &str,
+        permission: CallPermission,
+    ) -> Result<()> {
+        if permission == CallPermission::NoPerm
+            && self
+                .perm_funcs
+                .get(module_type)
+                .is_some_and(|names| names.contains(func_name))
+        {
+            return Err(ModuleManagerError::PermissionDenied(func_name.to_string()));
+        }
+        Ok(())
+    }
+
     /// 调用指定模块的指定方法（不可变调用）
     pub async fn call(
         &self,
         module_type: &ModuleType,
         func_name: &str,
         param: Value,
+        permission: CallPermission,
     ) -> Result<Value> {
         let module = self
             .modules
             .get(module_type)
             .ok_or_else(|| ModuleManagerError::ModuleNotFound(module_type.clone()))?;
+        self.check_permission(module_type, func_name, permission)?;
 
         let module_guard = module.read().await;
         module_guard
@@ -228,11 +337,13 @@ impl ModuleManager {
         module_type: &ModuleType,
         func_name: &str,
         param: Value,
+        permission: CallPermission,
     ) -> Result<Value> {
         let module = self
             .modules
             .get(module_type)
             .ok_or_else(|| ModuleManagerError::ModuleNotFound(module_type.clone()))?;
+        self.check_permission(module_type, func_name, permission)?;
 
         let mut module_guard = module.write().await;
         module_guard
@@ -247,11 +358,13 @@ impl ModuleManager {
         module_type: &ModuleType,
         func_name: &str,
         param: Value,
+        permission: CallPermission,
     ) -> Result<BoxStream<Value>> {
         let module = self
             .modules
             .get(module_type)
             .ok_or_else(|| ModuleManagerError::ModuleNotFound(module_type.clone()))?;
+        self.check_permission(module_type, func_name, permission)?;
 
         let module_guard = module.read().await;
         module_guard
@@ -276,6 +389,7 @@ impl Default for ModuleManagerConfig {
                 ModuleType::Embed(EmbedModule::Model),
                 ModuleType::Embed(EmbedModule::SceneManager),
             ],
+            agent: AgentConfig::default(),
         }
     }
 }
@@ -294,8 +408,8 @@ impl ModuleManagerConfig {
 
         embeds.sort_by_key(|embed| match embed {
             EmbedModule::BrowserControl => 0,
-            EmbedModule::MessagePool => 1,
-            EmbedModule::Model => 2,
+            EmbedModule::Model => 1,
+            EmbedModule::MessagePool => 2,
             EmbedModule::EdgeDeviceControl => 3,
             EmbedModule::SceneManager => 4,
         });
@@ -304,7 +418,10 @@ impl ModuleManagerConfig {
         enable_modules.extend(embeds.into_iter().map(ModuleType::Embed));
         enable_modules.extend(wasms.into_iter().map(ModuleType::Wasm));
 
-        ModuleManagerConfig { enable_modules }
+        ModuleManagerConfig {
+            enable_modules,
+            agent: self.agent,
+        }
     }
 }
 
@@ -344,12 +461,9 @@ impl<'de> Deserialize<'de> for EmbedModule {
     }
 }
 
-impl Serialize for ModuleType {
-    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let s = match self {
+impl Display for ModuleType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
             ModuleType::Embed(embed) => {
                 let embed_str = match embed {
                     EmbedModule::BrowserControl => "browser-control",
@@ -358,21 +472,26 @@ impl Serialize for ModuleType {
                     EmbedModule::MessagePool => "message-pool",
                     EmbedModule::SceneManager => "scene-manager",
                 };
-                format!("embed-{}", embed_str)
+                write!(f, "embed-{}", embed_str)
             }
-            ModuleType::Wasm(path) => format!("wasm-{}", path),
-        };
-        serializer.serialize_str(&s)
+            ModuleType::Wasm(path) => write!(f, "wasm-{}", path),
+        }
     }
 }
 
-impl<'de> Deserialize<'de> for ModuleType {
-    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+impl Serialize for ModuleType {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
-        D: Deserializer<'de>,
+        S: Serializer,
     {
-        let s = String::deserialize(deserializer)?;
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl FromStr for ModuleType {
+    type Err = String;
 
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
         if let Some(embed_name) = s.strip_prefix("embed-") {
             match embed_name {
                 "browser-control" => Ok(ModuleType::Embed(EmbedModule::BrowserControl)),
@@ -380,18 +499,25 @@ impl<'de> Deserialize<'de> for ModuleType {
                 "model" => Ok(ModuleType::Embed(EmbedModule::Model)),
                 "message-pool" => Ok(ModuleType::Embed(EmbedModule::MessagePool)),
                 "scene-manager" => Ok(ModuleType::Embed(EmbedModule::SceneManager)),
-                _ => Err(serde::de::Error::custom(format!(
-                    "unknown embed module: {}",
-                    embed_name
-                ))),
+                _ => Err(format!("unknown embed module: {}", embed_name)),
             }
         } else if let Some(wasm_path) = s.strip_prefix("wasm-") {
             Ok(ModuleType::Wasm(wasm_path.to_string()))
         } else {
-            Err(serde::de::Error::custom(format!(
+            Err(format!(
                 "invalid module type format: {}, expected 'embed-{{module}}' or 'wasm-{{path}}'",
                 s
-            )))
+            ))
         }
     }
 }
+
+impl<'de> Deserialize<'de> for ModuleType {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
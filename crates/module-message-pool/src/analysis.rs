@@ -1,25 +1,78 @@
 mod command_analysis;
 mod intent_recognition;
+mod media_preprocess;
+mod responder;
+mod rule_analysis;
 
 use crate::analysis::command_analysis::CommandAnalyzer;
 use crate::analysis::intent_recognition::IntentAnalyzer;
+use crate::analysis::media_preprocess::MediaPreprocessor;
+use crate::analysis::responder::ResponderAnalyzer;
+use crate::analysis::rule_analysis::RuleAnalyzer;
 use crate::error::*;
-use crate::{AnalyzerType, MessagePoolConfig};
+use crate::event::send_event;
+use crate::{
+    AnalysisQueueConfig, AnalyzerType, AvailableFunction, ContentData, MessagePoolConfig, Reply,
+    SceneEvent, scene_metadata,
+};
 use async_trait::async_trait;
+use chrono::Utc;
+use futures::FutureExt;
+use nihility_module::Module;
+use nihility_module_model::Model;
+use nihility_module_scene_manager::ReplyTarget;
+use nihility_store_operate::analysis_job::{self, AnalysisJobModel, AnalysisJobStatus};
+use nihility_store_operate::message::{self, MessageRole, MessageStatus, NewMessage};
+use nihility_store_operate::scene;
 use sea_orm::DatabaseConnection;
-use std::sync::Arc;
-use tokio::sync::mpsc;
-use tracing::{debug, error, info};
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::{RwLock, broadcast, mpsc};
+use tokio::task::{self, JoinSet};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+pub use intent_recognition::Intent;
+pub(crate) use rule_analysis::validate_rules;
+
+/// 清理成功任务的间隔
+const JOB_PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
 /// 分组分析任务（只包含 group_id）
 #[derive(Debug, Clone)]
 pub struct GroupIdTask {
     pub group_id: Uuid,
 }
 
+/// 分析器运行时依赖
+#[derive(Clone, Default)]
+pub struct AnalysisContext {
+    /// 模型模块引用
+    pub model: Option<Arc<RwLock<Model>>>,
+    /// 可供分析器使用的模块方法列表
+    pub available_functions: Arc<Vec<AvailableFunction>>,
+    /// 命令可调用的模块，键为模块类型字符串
+    ///
+    /// 使用弱引用，避免消息池经由自身持有的模块列表形成引用循环
+    pub modules: Arc<HashMap<String, Weak<RwLock<dyn Module + Send + Sync>>>>,
+    /// 场景事件发送端
+    pub event_tx: Option<broadcast::Sender<SceneEvent>>,
+}
+
+/// 分析器执行结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisOutcome {
+    /// 没有处理消息，继续执行后续分析器
+    Skipped,
+    /// 处理了消息，继续执行后续分析器
+    Handled,
+    /// 处理了消息并终止分析链
+    Stopped,
+}
+
 /// 分析器特征
-/// 返回 true 继续执行后续分析器，返回 false 终止分析链
 #[async_trait]
 pub trait Analyzer: Send + Sync {
     /// 分析器名称
@@ -29,72 +82,628 @@ pub trait Analyzer: Send + Sync {
     fn priority(&self) -> i32;
 
     /// 执行分析
-    async fn analyze(&self, db: &DatabaseConnection, group_id: Uuid) -> Result<bool>;
+    async fn analyze(&self, db: &DatabaseConnection, group_id: Uuid) -> Result<AnalysisOutcome>;
 }
 
 /// 根据配置创建分析器
-fn create_analyzer(config: &crate::AnalyzerConfig) -> Option<Arc<dyn Analyzer>> {
-    match &config.analyzer_type {
-        AnalyzerType::CommandAnalysis => {
-            Some(Arc::new(CommandAnalyzer::new(config.priority)) as Arc<dyn Analyzer>)
-        }
-        AnalyzerType::IntentRecognition => {
-            Some(Arc::new(IntentAnalyzer::new(config.priority)) as Arc<dyn Analyzer>)
-        }
+fn create_analyzer(
+    config: &crate::AnalyzerConfig,
+    pool_config: &MessagePoolConfig,
+    context: &AnalysisContext,
+) -> Result<Option<Arc<dyn Analyzer>>> {
+    Ok(match &config.analyzer_type {
+        AnalyzerType::CommandAnalysis => Some(Arc::new(CommandAnalyzer::new(
+            config.priority,
+            &pool_config.command_aliases,
+            context.modules.clone(),
+            context.available_functions.clone(),
+            context.event_tx.clone(),
+        )) as Arc<dyn Analyzer>),
+        AnalyzerType::RuleAnalysis => Some(Arc::new(RuleAnalyzer::new(
+            config.priority,
+            &pool_config.rules,
+            context.modules.clone(),
+            context.available_functions.clone(),
+            context.event_tx.clone(),
+        )?) as Arc<dyn Analyzer>),
+        AnalyzerType::MediaPreprocess => match context.model.as_ref() {
+            Some(model) => Some(Arc::new(MediaPreprocessor::new(
+                config.priority,
+                model.clone(),
+                pool_config.media.clone(),
+            )?) as Arc<dyn Analyzer>),
+            None => {
+                error!("model module does not exist for analyzer: media_preprocess");
+                None
+            }
+        },
+        AnalyzerType::IntentRecognition => match context.model.as_ref() {
+            Some(model) => Some(Arc::new(IntentAnalyzer::new(
+                config.priority,
+                model.clone(),
+                context.available_functions.clone(),
+            )) as Arc<dyn Analyzer>),
+            None => {
+                error!("model module does not exist for analyzer: intent_recognition");
+                None
+            }
+        },
+        AnalyzerType::Responder => match context.model.as_ref() {
+            Some(model) => Some(Arc::new(ResponderAnalyzer::new(
+                config.priority,
+                model.clone(),
+                context.event_tx.clone(),
+            )) as Arc<dyn Analyzer>),
+            None => {
+                error!("model module does not exist for analyzer: responder");
+                None
+            }
+        },
+    })
+}
+
+/// 补全模块名称，允许省略内置模块的 `embed-` 前缀
+pub(crate) fn resolve_module(
+    modules: &HashMap<String, Weak<RwLock<dyn Module + Send + Sync>>>,
+    module: &str,
+) -> String {
+    if modules.contains_key(module) {
+        return module.to_string();
+    }
+    let embed = format!("embed-{}", module);
+    if modules.contains_key(&embed) {
+        return embed;
+    }
+    module.to_string()
+}
+
+/// 将助手回复写回消息来源场景并通知订阅者
+///
+/// 回复使用新的消息组并标记为已处理，避免再次进入分析链
+pub(crate) async fn insert_reply(
+    db: &DatabaseConnection,
+    event_tx: Option<&broadcast::Sender<SceneEvent>>,
+    scene_id: Uuid,
+    reply_to_group_id: Uuid,
+    source: &str,
+    content: ContentData,
+) -> Result<()> {
+    let scene_id = reply_scene_id(db, scene_id).await?;
+    let msg = message::insert_message(
+        db,
+        NewMessage {
+            scene_id,
+            msg_type: content.to_msg_type(),
+            role: MessageRole::Assistant,
+            source: Some(source.to_string()),
+            reply_to_group_id: Some(reply_to_group_id),
+            content: serde_json::to_value(&content)?,
+            metadata: serde_json::json!({}),
+            group_id: Uuid::new_v4(),
+            is_processed: true,
+        },
+    )
+    .await?;
+    send_event(
+        event_tx,
+        SceneEvent::ReplyCreated(Reply {
+            scene_id,
+            message_id: msg.id,
+            reply_to_group_id,
+            content,
+        }),
+    );
+    Ok(())
+}
+
+/// 按场景元数据中的回复路由确定回复写入的场景，目标场景不存在时回复到来源场景
+async fn reply_scene_id(db: &DatabaseConnection, scene_id: Uuid) -> Result<Uuid> {
+    let scene = scene::find_scene_by_id(db, scene_id).await?;
+    let target = match scene_metadata(&scene).map(|metadata| metadata.reply_target) {
+        Some(ReplyTarget::Parent) => scene.parent_id.unwrap_or(scene.id),
+        Some(ReplyTarget::Scene { scene_id }) => scene_id,
+        Some(ReplyTarget::Source) | None => scene.id,
+    };
+    if target != scene.id && scene::find_scene_by_id(db, target).await.is_err() {
+        warn!(
+            "Reply target {} of scene {} not found, reply to source scene",
+            target, scene.id
+        );
+        return Ok(scene.id);
     }
+    Ok(target)
 }
 
 pub async fn analysis_worker(
     mut task_rx: mpsc::UnboundedReceiver<Uuid>,
     config: MessagePoolConfig,
     conn: DatabaseConnection,
+    context: AnalysisContext,
 ) -> Result<()> {
     // 根据配置创建分析器
-    let mut analyzers: Vec<Arc<dyn Analyzer>> = Vec::new();
+    let mut analyzers: Vec<TimedAnalyzer> = Vec::new();
+    let queue = config.analysis_queue.clone();
 
     for analyzer_config in &config.analyzers {
         if !analyzer_config.enabled {
             continue;
         }
-        if let Some(analyzer) = create_analyzer(analyzer_config) {
-            analyzers.push(analyzer);
+        if let Some(analyzer) = create_analyzer(analyzer_config, &config, &context)? {
+            analyzers.push(TimedAnalyzer {
+                analyzer,
+                timeout: Duration::from_secs(
+                    analyzer_config
+                        .timeout_secs
+                        .unwrap_or(queue.analyzer_timeout_secs)
+                        .max(1),
+                ),
+            });
         }
     }
 
     // 按优先级排序
-    analyzers.sort_by_key(|a| a.priority());
+    analyzers.sort_by_key(|a| a.analyzer.priority());
+    let analyzers: Arc<[TimedAnalyzer]> = analyzers.into();
 
-    info!("Analysis worker started with {} analyzers", analyzers.len());
-    for a in &analyzers {
-        info!("  - {} (priority: {})", a.name(), a.priority(),);
+    info!(
+        "Analysis worker started with {} analyzers, concurrency {}",
+        analyzers.len(),
+        queue.concurrency
+    );
+    for a in analyzers.iter() {
+        info!(
+            "  - {} (priority: {}, timeout: {}s)",
+            a.analyzer.name(),
+            a.analyzer.priority(),
+            a.timeout.as_secs()
+        );
     }
 
-    while let Some(group_id) = task_rx.recv().await {
-        info!("Received analysis task for group_id {}", group_id);
-
-        // 按优先级顺序执行分析链
-        for analyzer in &analyzers {
-            debug!("Running analyzer: {}", analyzer.name());
+    // 启动时恢复上次中断的任务
+    match analysis_job::reset_running_analysis_jobs(&conn).await {
+        Ok(0) => {}
+        Ok(count) => info!("Resumed {} interrupted analysis jobs", count),
+        Err(e) => error!("Reset running analysis jobs failed: {}", e),
+    }
 
-            match analyzer.analyze(&conn, group_id).await {
-                Ok(should_continue) => {
-                    if !should_continue {
-                        info!(
-                            "Analyzer {} returned false, terminating analysis chain",
-                            analyzer.name()
-                        );
-                        break;
+    let concurrency = queue.concurrency.max(1);
+    let poll_interval = Duration::from_secs(queue.poll_interval_secs.max(1));
+    // 正在执行的任务所在的场景，同一场景的任务依次执行
+    let mut running_scenes = HashMap::new();
+    let mut running = JoinSet::new();
+    let mut next_prune = tokio::time::Instant::now();
+    loop {
+        if tokio::time::Instant::now() >= next_prune {
+            prune_succeeded_jobs(&conn, &queue).await;
+            next_prune += JOB_PRUNE_INTERVAL;
+        }
+        let dispatched = match dispatch_due_jobs(
+            &conn,
+            &mut running_scenes,
+            &mut running,
+            concurrency,
+            &analyzers,
+            &context,
+            &queue,
+        )
+        .await
+        {
+            Ok(dispatched) => dispatched,
+            Err(e) => {
+                error!("Dispatch analysis jobs failed: {}", e);
+                0
+            }
+        };
+        let wait = if running.len() >= concurrency {
+            poll_interval
+        } else if dispatched > 0 {
+            // 可能还有未拉取到的任务
+            Duration::ZERO
+        } else {
+            match analysis_job::find_next_analysis_job_run_at(&conn).await {
+                // 已到期但所在场景正在执行的任务，在场景任务完成时分派
+                Ok(Some(next_run_at)) => (next_run_at.to_utc() - Utc::now())
+                    .to_std()
+                    .map_or(poll_interval, |wait| wait.min(poll_interval)),
+                Ok(None) => poll_interval,
+                Err(e) => {
+                    error!("Find next analysis job failed: {}", e);
+                    poll_interval
+                }
+            }
+        };
+        // 任务完成、新任务通知或等待到下一个任务的执行时间
+        tokio::select! {
+            Some(finished) = running.join_next_with_id(), if !running.is_empty() => {
+                // 任务 panic 时同样释放场景，避免场景的后续任务无法执行
+                let task_id = match finished {
+                    Ok((task_id, ())) => task_id,
+                    Err(join_err) => {
+                        error!("Analysis job join failed: {}", join_err);
+                        join_err.id()
                     }
+                };
+                running_scenes.remove(&task_id);
+            }
+            task = task_rx.recv() => {
+                if task.is_none() {
+                    break;
                 }
-                Err(e) => {
-                    error!("Analyzer {} failed: {}", analyzer.name(), e);
+            }
+            _ = tokio::time::sleep(wait) => {}
+        }
+    }
+
+    running.shutdown().await;
+    info!("Analysis worker stopped");
+    Ok(())
+}
+
+/// 删除超过保留时间的成功任务
+async fn prune_succeeded_jobs(conn: &DatabaseConnection, queue: &AnalysisQueueConfig) {
+    let Some(before) = i64::try_from(queue.succeeded_job_retention_secs)
+        .ok()
+        .and_then(chrono::Duration::try_seconds)
+        .and_then(|retention| Utc::now().checked_sub_signed(retention))
+    else {
+        return;
+    };
+    match analysis_job::delete_succeeded_analysis_jobs(conn, before.into()).await {
+        Ok(0) => {}
+        Ok(count) => info!("Pruned {} succeeded analysis jobs", count),
+        Err(e) => error!("Prune succeeded analysis jobs failed: {}", e),
+    }
+}
+
+/// 带超时时间的分析器
+struct TimedAnalyzer {
+    analyzer: Arc<dyn Analyzer>,
+    timeout: Duration,
+}
+
+/// 分派已到执行时间的任务，返回分派或跳过的任务数量
+///
+/// 同一场景同时只执行一个任务，按任务的创建顺序依次执行
+async fn dispatch_due_jobs(
+    conn: &DatabaseConnection,
+    running_scenes: &mut HashMap<task::Id, Uuid>,
+    running: &mut JoinSet<()>,
+    concurrency: usize,
+    analyzers: &Arc<[TimedAnalyzer]>,
+    context: &AnalysisContext,
+    queue: &AnalysisQueueConfig,
+) -> Result<usize> {
+    if running.len() >= concurrency {
+        return Ok(0);
+    }
+    let running_scene_ids = running_scenes.values().copied().collect::<Vec<_>>();
+    let jobs = analysis_job::find_due_analysis_jobs(
+        conn,
+        Utc::now().into(),
+        &running_scene_ids,
+        (concurrency - running.len()) as u64,
+    )
+    .await?;
+    let mut dispatched = 0;
+    for job in jobs {
+        let Some(scene_id) = message::find_message_by_group_id(conn, job.group_id)
+            .await?
+            .first()
+            .map(|msg| msg.scene_id)
+        else {
+            // 消息组已被删除，没有需要分析的消息
+            info!("Messages of analysis job {} were deleted, skipping", job.id);
+            analysis_job::finish_analysis_job(
+                conn,
+                job.id,
+                AnalysisJobStatus::Succeeded,
+                None,
+                None,
+            )
+            .await?;
+            dispatched += 1;
+            continue;
+        };
+        if running_scenes.values().any(|running| *running == scene_id) {
+            continue;
+        }
+        // 分派前标记为执行中，避免下次拉取时重复分派
+        let job = analysis_job::start_analysis_job(conn, job).await?;
+        let conn = conn.clone();
+        let analyzers = analyzers.clone();
+        let context = context.clone();
+        let queue = queue.clone();
+        let handle = running.spawn(async move {
+            execute_job(&conn, &analyzers, &context, &queue, job, scene_id).await;
+        });
+        running_scenes.insert(handle.id(), scene_id);
+        dispatched += 1;
+    }
+    Ok(dispatched)
+}
+
+/// 执行分析任务，执行出错或 panic 时同样结束本次执行
+///
+/// 任务停留在执行中会阻塞所在场景的后续任务，直到进程重启
+async fn execute_job(
+    conn: &DatabaseConnection,
+    analyzers: &[TimedAnalyzer],
+    context: &AnalysisContext,
+    queue: &AnalysisQueueConfig,
+    job: AnalysisJobModel,
+    scene_id: Uuid,
+) {
+    let result = AssertUnwindSafe(run_job(
+        conn,
+        analyzers,
+        context,
+        queue,
+        job.clone(),
+        scene_id,
+    ))
+    .catch_unwind()
+    .await
+    .unwrap_or_else(|_| {
+        Err(MessagePoolError::Analysis(
+            "analysis job panicked".to_string(),
+        ))
+    });
+    let Err(e) = result else {
+        return;
+    };
+    error!("Analysis job {} failed: {}", job.id, e);
+    let final_attempt = job.attempts as u32 >= queue.max_attempts.max(1);
+    if let Err(e) = finish_failed_job(conn, queue, &job, e.to_string()).await {
+        error!("Finish analysis job {} failed: {}", job.id, e);
+    }
+    let status = if final_attempt {
+        MessageStatus::Failed
+    } else {
+        MessageStatus::Pending
+    };
+    if let Err(e) = message::update_group_status(conn, job.group_id, status, None).await {
+        error!("Update status of group_id {} failed: {}", job.group_id, e);
+    }
+}
+
+/// 结束失败的任务执行，最后一次尝试时标记为失败，否则按指数退避等待重试
+async fn finish_failed_job(
+    conn: &DatabaseConnection,
+    queue: &AnalysisQueueConfig,
+    job: &AnalysisJobModel,
+    error: String,
+) -> Result<()> {
+    if job.attempts as u32 >= queue.max_attempts.max(1) {
+        warn!(
+            "Analysis job {} failed after {} attempts",
+            job.id, job.attempts
+        );
+        analysis_job::finish_analysis_job(
+            conn,
+            job.id,
+            AnalysisJobStatus::Failed,
+            Some(error),
+            None,
+        )
+        .await?;
+    } else {
+        // 指数退避：base * 2^(attempts - 1)，不超过最大间隔
+        let backoff = queue
+            .retry_base_secs
+            .saturating_mul(1u64 << (job.attempts - 1).clamp(0, 20))
+            .min(queue.retry_max_secs);
+        info!("Analysis job {} will retry in {}s", job.id, backoff);
+        analysis_job::finish_analysis_job(
+            conn,
+            job.id,
+            AnalysisJobStatus::Pending,
+            Some(error),
+            Some((Utc::now() + chrono::Duration::seconds(backoff as i64)).into()),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// 执行一次已标记为执行中的分析任务
+///
+/// 已完成的分析器在重试时跳过。分析器失败或超时时暂停分析链等待重试，
+/// 最后一次尝试时跳过失败的分析器继续执行后续分析器
+async fn run_job(
+    conn: &DatabaseConnection,
+    analyzers: &[TimedAnalyzer],
+    context: &AnalysisContext,
+    queue: &AnalysisQueueConfig,
+    job: AnalysisJobModel,
+    scene_id: Uuid,
+) -> Result<()> {
+    let group_id = job.group_id;
+    let final_attempt = job.attempts as u32 >= queue.max_attempts.max(1);
+    let mut completed: Vec<String> =
+        serde_json::from_value(job.completed_analyzers.clone()).unwrap_or_default();
+    info!(
+        "Running analysis job {} for group_id {} (attempt {})",
+        job.id, group_id, job.attempts
+    );
+    send_event(
+        context.event_tx.as_ref(),
+        SceneEvent::AnalysisStarted { scene_id, group_id },
+    );
+    // 上次尝试中处理了消息的分析器，重试时保留
+    let mut handled_by = message::find_message_by_group_id(conn, group_id)
+        .await?
+        .into_iter()
+        .next()
+        .filter(|msg| msg.status == MessageStatus::Pending)
+        .and_then(|msg| msg.handled_by);
+    message::update_group_status(
+        conn,
+        group_id,
+        MessageStatus::Analyzing,
+        handled_by.as_deref(),
+    )
+    .await?;
+    let metadata = scene_metadata(&scene::find_scene_by_id(conn, scene_id).await?);
+
+    // 按优先级顺序执行分析链，跳过场景未启用的分析器
+    let mut errors = Vec::new();
+    let mut failed_analyzer = None;
+    for TimedAnalyzer { analyzer, timeout } in analyzers {
+        if completed.iter().any(|name| name == analyzer.name()) {
+            continue;
+        }
+        if let Some(metadata) = &metadata
+            && !metadata.analyzer_enabled(analyzer.name())
+        {
+            debug!("Analyzer {} disabled in scene", analyzer.name());
+            continue;
+        }
+        debug!("Running analyzer: {}", analyzer.name());
+
+        let result = tokio::time::timeout(*timeout, analyzer.analyze(conn, group_id))
+            .await
+            .unwrap_or_else(|_| {
+                Err(MessagePoolError::Analysis(format!(
+                    "timed out after {}s",
+                    timeout.as_secs()
+                )))
+            });
+        match result {
+            Ok(outcome) => {
+                completed.push(analyzer.name().to_string());
+                analysis_job::update_analysis_job_completed(conn, job.id, &completed).await?;
+                if outcome != AnalysisOutcome::Skipped {
+                    handled_by = Some(analyzer.name().to_string());
+                }
+                if outcome == AnalysisOutcome::Stopped {
+                    info!(
+                        "Analyzer {} stopped, terminating analysis chain",
+                        analyzer.name()
+                    );
+                    break;
+                }
+            }
+            Err(e) => {
+                error!("Analyzer {} failed: {}", analyzer.name(), e);
+                errors.push(format!("{}: {}", analyzer.name(), e));
+                failed_analyzer.get_or_insert(analyzer.name());
+                if !final_attempt {
+                    break;
                 }
             }
         }
+    }
+
+    send_event(
+        context.event_tx.as_ref(),
+        SceneEvent::AnalysisFinished { scene_id, group_id },
+    );
+    // 最后处理了消息的分析器视为处理者，没有分析器处理消息时视为忽略
+    let (status, handled_by) = if !errors.is_empty() {
+        if final_attempt {
+            (MessageStatus::Failed, failed_analyzer)
+        } else {
+            (MessageStatus::Pending, handled_by.as_deref())
+        }
+    } else if let Some(name) = handled_by.as_deref() {
+        (MessageStatus::Handled, Some(name))
+    } else {
+        (MessageStatus::Ignored, None)
+    };
+    message::update_group_status(conn, group_id, status, handled_by).await?;
 
+    if errors.is_empty() {
         info!("Analysis chain completed for group_id {}", group_id);
+        analysis_job::finish_analysis_job(conn, job.id, AnalysisJobStatus::Succeeded, None, None)
+            .await?;
+    } else {
+        finish_failed_job(conn, queue, &job, errors.join("\n")).await?;
     }
-
-    info!("Analysis worker stopped");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nihility_store_migration::{Migrator, MigratorTrait};
+    use sea_orm::Database;
+
+    /// 创建包含一条待分析消息的执行中任务
+    async fn running_job(conn: &DatabaseConnection) -> AnalysisJobModel {
+        let scene = scene::find_scene_by_name(conn, "base")
+            .await
+            .unwrap()
+            .unwrap();
+        let group_id = Uuid::new_v4();
+        message::insert_message(
+            conn,
+            NewMessage {
+                scene_id: scene.id,
+                msg_type: message::MsgType::Text,
+                role: MessageRole::User,
+                source: None,
+                reply_to_group_id: None,
+                content: serde_json::to_value(ContentData::Text {
+                    body: "hello".to_string(),
+                })
+                .unwrap(),
+                metadata: serde_json::json!({}),
+                group_id,
+                is_processed: false,
+            },
+        )
+        .await
+        .unwrap();
+        let job = analysis_job::insert_analysis_job(conn, group_id)
+            .await
+            .unwrap();
+        analysis_job::start_analysis_job(conn, job).await.unwrap()
+    }
+
+    /// 开始执行后读取不存在的场景，模拟执行过程中的存储错误
+    async fn execute_with_store_error(queue: &AnalysisQueueConfig) -> AnalysisJobModel {
+        let conn = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&conn, None).await.unwrap();
+        let job = running_job(&conn).await;
+        let analyzers: Arc<[TimedAnalyzer]> = Vec::new().into();
+        execute_job(
+            &conn,
+            &analyzers,
+            &AnalysisContext::default(),
+            queue,
+            job.clone(),
+            Uuid::new_v4(),
+        )
+        .await;
+        let messages = message::find_message_by_group_id(&conn, job.group_id)
+            .await
+            .unwrap();
+        assert!(
+            messages
+                .iter()
+                .all(|msg| msg.status != MessageStatus::Analyzing)
+        );
+        analysis_job::find_analysis_job_by_id(&conn, job.id)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn store_error_schedules_retry() {
+        let job = execute_with_store_error(&AnalysisQueueConfig::default()).await;
+        assert_eq!(job.status, AnalysisJobStatus::Pending);
+        assert!(job.last_error.is_some());
+        assert!(job.next_run_at.to_utc() > Utc::now());
+    }
+
+    #[tokio::test]
+    async fn store_error_on_final_attempt_fails_job() {
+        let queue = AnalysisQueueConfig {
+            max_attempts: 1,
+            ..Default::default()
+        };
+        let job = execute_with_store_error(&queue).await;
+        assert_eq!(job.status, AnalysisJobStatus::Failed);
+        assert!(job.last_error.is_some());
+    }
+}
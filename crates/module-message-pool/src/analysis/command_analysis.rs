@@ -1,21 +1,137 @@
-use crate::{ContentData, MessagePoolError};
+use crate::{AvailableFunction, CommandAlias, ContentData, MessagePoolError, SceneEvent};
 use async_trait::async_trait;
+use nihility_module::Module;
 use nihility_store_operate::message;
 use sea_orm::DatabaseConnection;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use tokio::sync::{RwLock, broadcast};
 use uuid::Uuid;
 
-use crate::analysis::Analyzer;
+use crate::analysis::{AnalysisOutcome, Analyzer, insert_reply, resolve_module};
+
+/// 解析后的命令
+#[derive(Debug, Clone, PartialEq)]
+struct Command {
+    module: String,
+    function: String,
+    param: Value,
+}
 
 /// 命令分析器
-/// 检查消息是否以 `/` 开头，如果是则终止分析链
+/// 检查消息是否以 `/` 开头，如果是则执行命令、回写结果并终止分析链
+///
+/// 支持 `/module function {json}` 以及配置的别名，例如 `/scene list`
 pub struct CommandAnalyzer {
     priority: i32,
+    /// 别名单词列表，按单词数量降序排列以优先匹配更长的别名
+    aliases: Vec<(Vec<String>, CommandAlias)>,
+    modules: Arc<HashMap<String, Weak<RwLock<dyn Module + Send + Sync>>>>,
+    functions: Arc<Vec<AvailableFunction>>,
+    event_tx: Option<broadcast::Sender<SceneEvent>>,
 }
 
 impl CommandAnalyzer {
-    pub fn new(priority: i32) -> Self {
-        Self { priority }
+    pub fn new(
+        priority: i32,
+        aliases: &[CommandAlias],
+        modules: Arc<HashMap<String, Weak<RwLock<dyn Module + Send + Sync>>>>,
+        functions: Arc<Vec<AvailableFunction>>,
+        event_tx: Option<broadcast::Sender<SceneEvent>>,
+    ) -> Self {
+        let mut aliases = aliases
+            .iter()
+            .map(|alias| {
+                let words = alias.name.split_whitespace().map(String::from).collect();
+                (words, alias.clone())
+            })
+            .collect::<Vec<(Vec<String>, CommandAlias)>>();
+        aliases.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+        Self {
+            priority,
+            aliases,
+            modules,
+            functions,
+            event_tx,
+        }
     }
+
+    /// 解析命令文本（不含开头的 `/`）
+    fn parse(&self, body: &str) -> Result<Command, String> {
+        let tokens = body.split_whitespace().collect::<Vec<_>>();
+
+        let (module, function, rest) = match self
+            .aliases
+            .iter()
+            .find(|(words, _)| !words.is_empty() && starts_with_words(&tokens, words))
+        {
+            Some((words, alias)) => (
+                alias.module.clone(),
+                alias.function.clone(),
+                skip_words(body, words.len()),
+            ),
+            None => match tokens.as_slice() {
+                [module, function, ..] => (
+                    module.to_string(),
+                    function.to_string(),
+                    skip_words(body, 2),
+                ),
+                _ => return Err(format!("未知命令: /{}", body)),
+            },
+        };
+
+        let param = if rest.is_empty() {
+            Value::Object(Default::default())
+        } else {
+            serde_json::from_str(rest).map_err(|e| format!("命令参数不是合法的 JSON: {}", e))?
+        };
+        Ok(Command {
+            module: resolve_module(&self.modules, &module),
+            function,
+            param,
+        })
+    }
+
+    /// 执行命令，返回回复文本
+    async fn execute(&self, body: &str) -> Result<String, String> {
+        let command = self.parse(body)?;
+        // 命令来源于消息，仅允许调用低权限方法
+        if !self.functions.iter().any(|function| {
+            function.module == command.module && function.metadata.name == command.function
+        }) {
+            return Err(format!("未知命令: /{}", body));
+        }
+        let module = self
+            .modules
+            .get(&command.module)
+            .and_then(Weak::upgrade)
+            .ok_or_else(|| format!("模块不存在: {}", command.module))?;
+        let result = module
+            .read()
+            .await
+            .call(&command.function, command.param)
+            .await
+            .map_err(|e| format!("命令执行失败: {}", e))?;
+        serde_json::to_string_pretty(&result).map_err(|e| format!("命令结果序列化失败: {}", e))
+    }
+}
+
+/// 判断命令是否以别名的全部单词开头
+fn starts_with_words(tokens: &[&str], words: &[String]) -> bool {
+    tokens.len() >= words.len() && tokens.iter().zip(words).all(|(token, word)| token == word)
+}
+
+/// 跳过开头的若干个单词，返回剩余文本
+fn skip_words(body: &str, count: usize) -> &str {
+    let mut rest = body.trim_start();
+    for _ in 0..count {
+        rest = rest
+            .find(char::is_whitespace)
+            .map_or("", |index| &rest[index..])
+            .trim_start();
+    }
+    rest.trim_end()
 }
 
 #[async_trait]
@@ -32,28 +148,49 @@ impl Analyzer for CommandAnalyzer {
         &self,
         db: &DatabaseConnection,
         group_id: Uuid,
-    ) -> Result<bool, MessagePoolError> {
+    ) -> Result<AnalysisOutcome, MessagePoolError> {
         // 根据 group_id 拉取所有消息
         let messages = message::find_message_by_group_id(db, group_id).await?;
 
+        let mut detected = false;
         for msg in messages {
             let content: ContentData = serde_json::from_value(msg.content)?;
 
             // 检查是否是文本消息且以 `/` 开头
             if let ContentData::Text { body } = content
-                && body.starts_with('/')
+                && let Some(command) = body.strip_prefix('/')
             {
                 tracing::info!(
                     "Command detected in group_id {}: {}",
                     group_id,
                     body.split_whitespace().next().unwrap_or(&body)
                 );
-                // 命令分析器检测到命令，终止后续分析链
-                return Ok(false);
+                detected = true;
+
+                let reply = match self.execute(command).await {
+                    Ok(reply) => reply,
+                    Err(reply) => {
+                        tracing::warn!("Command failed in group_id {}: {}", group_id, reply);
+                        reply
+                    }
+                };
+                insert_reply(
+                    db,
+                    self.event_tx.as_ref(),
+                    msg.scene_id,
+                    group_id,
+                    "command",
+                    ContentData::Text { body: reply },
+                )
+                .await?;
             }
         }
 
-        // 未检测到命令，继续分析链
-        Ok(true)
+        // 检测到命令时终止后续分析链
+        Ok(if detected {
+            AnalysisOutcome::Stopped
+        } else {
+            AnalysisOutcome::Skipped
+        })
     }
 }
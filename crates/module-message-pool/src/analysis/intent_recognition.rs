@@ -1,20 +1,102 @@
-use crate::MessagePoolError;
+use crate::{AvailableFunction, ContentData, MessagePoolError};
 use async_trait::async_trait;
-use nihility_store_operate::message;
+use nihility_module_model::Model;
+use nihility_module_model::func::text_completion::TextCompletionParam;
+use nihility_store_operate::{analysis_result, message, scene};
+use schemars::JsonSchema;
 use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::analysis::Analyzer;
+use crate::analysis::{AnalysisOutcome, Analyzer};
+
+/// 意图识别结果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Intent {
+    /// 目标模块，无匹配时为空
+    pub module: Option<String>,
+    /// 目标方法，无匹配时为空
+    pub function: Option<String>,
+    /// 方法参数
+    #[serde(default)]
+    pub params: serde_json::Value,
+    /// 置信度（0.0 - 1.0）
+    #[serde(default)]
+    pub confidence: f32,
+}
 
 /// 意图识别器
 /// 使用提示词方式进行意图识别
 pub struct IntentAnalyzer {
     priority: i32,
+    model: Arc<RwLock<Model>>,
+    functions: Arc<Vec<AvailableFunction>>,
 }
 
 impl IntentAnalyzer {
-    pub fn new(priority: i32) -> Self {
-        Self { priority }
+    pub fn new(
+        priority: i32,
+        model: Arc<RwLock<Model>>,
+        functions: Arc<Vec<AvailableFunction>>,
+    ) -> Self {
+        Self {
+            priority,
+            model,
+            functions,
+        }
+    }
+
+    /// 构建意图识别提示词
+    fn build_prompt(&self, scene_description: Option<&str>, messages: &[String]) -> String {
+        let mut prompt =
+            String::from("你是一个意图识别助手，请根据用户消息判断需要调用的模块方法。\n");
+        if let Some(description) = scene_description {
+            let _ = writeln!(prompt, "\n当前场景：{}", description);
+        }
+        prompt.push_str("\n可用方法：\n");
+        for function in self.functions.iter() {
+            let _ = writeln!(
+                prompt,
+                "- 模块: {}, 方法: {}, 描述: {}, 参数: {}",
+                function.module,
+                function.metadata.name,
+                function.metadata.desc,
+                function.metadata.params
+            );
+        }
+        prompt.push_str("\n用户消息：\n");
+        for msg in messages {
+            let _ = writeln!(prompt, "{}", msg);
+        }
+        prompt.push_str(
+            "\n请仅输出一个 JSON 对象，格式为 \
+             {\"module\": 模块名或null, \"function\": 方法名或null, \"params\": 参数对象, \"confidence\": 0到1之间的小数}，\
+             无法匹配任何方法时 module 与 function 为 null。",
+        );
+        prompt
+    }
+}
+
+/// 从模型输出中提取意图 JSON
+fn parse_intent(output: &str) -> Result<Intent, MessagePoolError> {
+    // 去除推理模型输出的思考内容
+    let output = match output.rfind("</think>") {
+        Some(index) => &output[index + "</think>".len()..],
+        None => output,
+    };
+    let start = output.find('{');
+    let end = output.rfind('}');
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => {
+            Ok(serde_json::from_str::<Intent>(&output[start..=end])?)
+        }
+        _ => Err(MessagePoolError::Analysis(format!(
+            "no intent json found in model output: {}",
+            output
+        ))),
     }
 }
 
@@ -32,20 +114,52 @@ impl Analyzer for IntentAnalyzer {
         &self,
         db: &DatabaseConnection,
         group_id: Uuid,
-    ) -> Result<bool, MessagePoolError> {
+    ) -> Result<AnalysisOutcome, MessagePoolError> {
         // 根据 group_id 拉取所有消息
         let messages = message::find_message_by_group_id(db, group_id).await?;
+        let Some(first) = messages.first() else {
+            return Ok(AnalysisOutcome::Skipped);
+        };
+        let scene = scene::find_scene_by_id(db, first.scene_id).await?;
+        let scene_description = scene
+            .metadata
+            .get("description")
+            .and_then(|description| description.as_str());
+
+        let mut texts = Vec::with_capacity(messages.len());
+        for msg in messages.iter() {
+            let content: ContentData = serde_json::from_value(msg.content.clone())?;
+            texts.push(match content {
+                ContentData::Summary { body } => format!("[摘要] {}", body),
+                content => content.to_text(&msg.metadata),
+            });
+        }
 
-        // TODO: 实现意图识别逻辑
-        // 可以遍历消息内容，使用 AI 模型进行意图识别
+        let prompt = self.build_prompt(scene_description, &texts);
+        let output = self
+            .model
+            .read()
+            .await
+            .text_completion(&TextCompletionParam { prompt })
+            .await?;
+        let intent = parse_intent(&output)?;
 
         tracing::info!(
-            "IntentAnalyzer processed {} messages in group_id {}",
-            messages.len(),
-            group_id
+            "IntentAnalyzer recognized intent in group_id {}: {:?}.{:?} ({})",
+            group_id,
+            intent.module,
+            intent.function,
+            intent.confidence
         );
+        analysis_result::insert_analysis_result(
+            db,
+            group_id,
+            self.name(),
+            serde_json::to_value(&intent)?,
+        )
+        .await?;
 
-        // 意图识别不终止分析链
-        Ok(true)
+        // 意图识别只记录结果，不视为处理了消息
+        Ok(AnalysisOutcome::Skipped)
     }
 }
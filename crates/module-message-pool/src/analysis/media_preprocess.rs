@@ -0,0 +1,158 @@
+use crate::media::{self, KeyframeOptions, MediaFetcher};
+use crate::{ContentData, DERIVED_TEXT_METADATA_KEY, MediaConfig, MessagePoolError};
+use async_trait::async_trait;
+use nihility_module_model::Model;
+use nihility_module_model::func::image_understanding::ImageUnderstandingParam;
+use nihility_module_model::func::speech_recognition::SpeechRecognitionParam;
+use nihility_store_operate::message;
+use sea_orm::DatabaseConnection;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::analysis::{AnalysisOutcome, Analyzer};
+
+/// 多模态预处理器
+/// 音频经语音识别转为文本，图片与视频关键帧经图片理解生成描述，
+/// 结果写入消息元数据的 `derived_text` 字段供后续文本分析器使用
+///
+/// 已有派生文本的消息会被跳过，单条消息失败不影响同组其他消息，任务重试时只处理失败的消息
+pub struct MediaPreprocessor {
+    priority: i32,
+    model: Arc<RwLock<Model>>,
+    fetcher: MediaFetcher,
+    config: MediaConfig,
+}
+
+impl MediaPreprocessor {
+    pub fn new(
+        priority: i32,
+        model: Arc<RwLock<Model>>,
+        config: MediaConfig,
+    ) -> Result<Self, MessagePoolError> {
+        Ok(Self {
+            priority,
+            model,
+            fetcher: MediaFetcher::new(&config)?,
+            config,
+        })
+    }
+
+    /// 语音识别
+    async fn transcribe(&self, source: &str) -> Result<String, MessagePoolError> {
+        let bytes = self.fetcher.load_source(source).await?;
+        let audio_data = tokio::task::spawn_blocking(move || media::decode_audio(bytes))
+            .await
+            .map_err(|e| MessagePoolError::Media(format!("audio decode task failed: {}", e)))??;
+        Ok(self
+            .model
+            .read()
+            .await
+            .speech_recognition(SpeechRecognitionParam { audio_data })
+            .await?)
+    }
+
+    /// 生成图片描述
+    async fn caption(&self, image_url: String) -> Result<String, MessagePoolError> {
+        Ok(self
+            .model
+            .read()
+            .await
+            .image_understanding(&ImageUnderstandingParam {
+                image_url,
+                prompt: self.config.caption_prompt.clone(),
+            })
+            .await?)
+    }
+
+    /// 抽取视频关键帧并逐帧生成描述
+    async fn describe_video(&self, source: &str) -> Result<String, MessagePoolError> {
+        let video = self.fetcher.load_source(source).await?;
+        let frames = media::extract_keyframes(
+            &video,
+            &KeyframeOptions {
+                ffmpeg_path: &self.config.ffmpeg_path,
+                max_frames: self.config.video_max_frames,
+                interval_secs: self.config.video_frame_interval_secs,
+            },
+        )
+        .await?;
+        if frames.is_empty() {
+            return Err(MessagePoolError::Media(
+                "no keyframe extracted from video".to_string(),
+            ));
+        }
+        let mut captions = Vec::with_capacity(frames.len());
+        for (index, frame) in frames.into_iter().enumerate() {
+            let caption = self.caption(frame).await?;
+            captions.push(format!("第{}帧：{}", index + 1, caption.trim()));
+        }
+        Ok(captions.join("\n"))
+    }
+}
+
+#[async_trait]
+impl Analyzer for MediaPreprocessor {
+    fn name(&self) -> &str {
+        "media_preprocess"
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    async fn analyze(
+        &self,
+        db: &DatabaseConnection,
+        group_id: Uuid,
+    ) -> Result<AnalysisOutcome, MessagePoolError> {
+        let messages = message::find_message_by_group_id(db, group_id).await?;
+
+        let mut failed = None;
+        for msg in messages {
+            if msg.metadata.get(DERIVED_TEXT_METADATA_KEY).is_some() {
+                continue;
+            }
+            let content: ContentData = serde_json::from_value(msg.content)?;
+            let result = match &content {
+                ContentData::Audio { source } => self.transcribe(source).await,
+                ContentData::Image { source } => {
+                    match media::image_url(source, self.config.max_source_bytes) {
+                        Ok(image_url) => self.caption(image_url).await,
+                        Err(e) => Err(e),
+                    }
+                }
+                ContentData::Video { source } => self.describe_video(source).await,
+                ContentData::Text { .. } | ContentData::Summary { .. } => continue,
+            };
+            let text = match result {
+                Ok(text) => text.trim().to_string(),
+                Err(e) => {
+                    tracing::warn!("Failed to preprocess message {}: {}", msg.id, e);
+                    failed.get_or_insert(e);
+                    continue;
+                }
+            };
+
+            tracing::info!(
+                "Derived text for message {} in group_id {}",
+                msg.id,
+                group_id
+            );
+            let mut metadata = msg.metadata;
+            match metadata.as_object_mut() {
+                Some(object) => {
+                    object.insert(DERIVED_TEXT_METADATA_KEY.to_string(), Value::String(text));
+                }
+                None => metadata = serde_json::json!({ DERIVED_TEXT_METADATA_KEY: text }),
+            }
+            message::update_message_metadata(db, msg.id, metadata).await?;
+        }
+
+        match failed {
+            Some(e) => Err(e),
+            None => Ok(AnalysisOutcome::Skipped),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::func::build_context::{
+    BuildContextParam, build_context, default_max_tokens, default_recent_limit,
+    default_unprocessed_limit,
+};
+use crate::{ContentData, MessagePoolError, SceneEvent, scene_metadata};
+use async_trait::async_trait;
+use nihility_module_model::Model;
+use nihility_module_model::func::chat_completion::ChatCompletionParam;
+use nihility_store_operate::{message, scene};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tokio::sync::{RwLock, broadcast};
+use uuid::Uuid;
+
+use crate::analysis::{AnalysisOutcome, Analyzer, insert_reply};
+
+/// 回复生成的默认系统提示词，可由场景元数据的 `system_prompt` 覆盖
+const RESPONDER_PROMPT: &str =
+    "你是 nihility 助手，请结合场景信息和对话历史，简洁地回复用户最新的消息。";
+
+/// 回复生成器
+/// 以消息所在场景构建上下文，使用模型生成回复并写回同一场景
+/// 场景元数据设置了 `model` 时优先使用该模型
+pub struct ResponderAnalyzer {
+    priority: i32,
+    model: Arc<RwLock<Model>>,
+    event_tx: Option<broadcast::Sender<SceneEvent>>,
+}
+
+impl ResponderAnalyzer {
+    pub fn new(
+        priority: i32,
+        model: Arc<RwLock<Model>>,
+        event_tx: Option<broadcast::Sender<SceneEvent>>,
+    ) -> Self {
+        Self {
+            priority,
+            model,
+            event_tx,
+        }
+    }
+}
+
+#[async_trait]
+impl Analyzer for ResponderAnalyzer {
+    fn name(&self) -> &str {
+        "responder"
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    async fn analyze(
+        &self,
+        db: &DatabaseConnection,
+        group_id: Uuid,
+    ) -> Result<AnalysisOutcome, MessagePoolError> {
+        let messages = message::find_message_by_group_id(db, group_id).await?;
+        let Some(first) = messages.first() else {
+            return Ok(AnalysisOutcome::Skipped);
+        };
+        let scene_id = first.scene_id;
+        let metadata = scene_metadata(&scene::find_scene_by_id(db, scene_id).await?);
+        let (system_prompt, model) = match metadata {
+            Some(metadata) => (metadata.system_prompt, metadata.model),
+            None => (None, None),
+        };
+        let system_prompt = system_prompt.unwrap_or_else(|| RESPONDER_PROMPT.to_string());
+
+        let context = build_context(
+            db,
+            Some(&self.model),
+            &BuildContextParam {
+                scene_id,
+                include_children: false,
+                recent_limit: default_recent_limit(),
+                unprocessed_limit: default_unprocessed_limit(),
+                max_tokens: default_max_tokens(),
+                system_prompt: Some(system_prompt),
+            },
+        )
+        .await?;
+        let output = self
+            .model
+            .read()
+            .await
+            .chat_completion(&ChatCompletionParam {
+                messages: context.messages,
+                model,
+            })
+            .await?;
+        // 去除推理模型输出的思考内容
+        let body = match output.rfind("</think>") {
+            Some(index) => &output[index + "</think>".len()..],
+            None => output.as_str(),
+        }
+        .trim()
+        .to_string();
+        if body.is_empty() {
+            return Err(MessagePoolError::Analysis(
+                "empty reply from model output".to_string(),
+            ));
+        }
+
+        tracing::info!(
+            "Responder replied to group_id {} in scene {}",
+            group_id,
+            scene_id
+        );
+        insert_reply(
+            db,
+            self.event_tx.as_ref(),
+            scene_id,
+            group_id,
+            "responder",
+            ContentData::Text { body },
+        )
+        .await?;
+
+        Ok(AnalysisOutcome::Handled)
+    }
+}
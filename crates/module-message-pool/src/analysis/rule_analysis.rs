@@ -0,0 +1,358 @@
+use crate::{
+    AnalysisRule, AvailableFunction, ContentData, DERIVED_TEXT_METADATA_KEY, MessagePoolError,
+    SceneEvent,
+};
+use async_trait::async_trait;
+use nihility_module::Module;
+use nihility_store_operate::{analysis_result, message, scene};
+use regex::{Captures, Regex};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Weak};
+use tokio::sync::{RwLock, broadcast};
+use uuid::Uuid;
+
+use crate::analysis::{AnalysisOutcome, Analyzer, insert_reply, resolve_module};
+
+/// 编译后的规则
+struct CompiledRule {
+    rule: AnalysisRule,
+    regex: Option<Regex>,
+}
+
+/// 规则命中记录，每次调用后立即写入分析结果
+#[derive(Debug, Serialize, Deserialize)]
+struct RuleHit {
+    rule: String,
+    message_id: Uuid,
+    module: String,
+    function: String,
+    result: Option<Value>,
+    error: Option<String>,
+}
+
+/// 规则分析器
+/// 按配置顺序匹配消息，命中后以模板参数调用模块方法
+///
+/// 每条消息仅触发第一条命中的规则，调用失败只记录不重试。
+/// 任务重试时跳过已有命中记录的消息，避免重复产生副作用
+pub struct RuleAnalyzer {
+    priority: i32,
+    rules: Vec<CompiledRule>,
+    modules: Arc<HashMap<String, Weak<RwLock<dyn Module + Send + Sync>>>>,
+    functions: Arc<Vec<AvailableFunction>>,
+    event_tx: Option<broadcast::Sender<SceneEvent>>,
+}
+
+impl RuleAnalyzer {
+    pub fn new(
+        priority: i32,
+        rules: &[AnalysisRule],
+        modules: Arc<HashMap<String, Weak<RwLock<dyn Module + Send + Sync>>>>,
+        functions: Arc<Vec<AvailableFunction>>,
+        event_tx: Option<broadcast::Sender<SceneEvent>>,
+    ) -> Result<Self, MessagePoolError> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let regex = compile_regex(rule)?;
+                let mut rule = rule.clone();
+                rule.module = resolve_module(&modules, &rule.module);
+                Ok(CompiledRule { rule, regex })
+            })
+            .collect::<Result<_, MessagePoolError>>()?;
+        Ok(Self {
+            priority,
+            rules,
+            modules,
+            functions,
+            event_tx,
+        })
+    }
+
+    /// 调用规则对应的模块方法
+    async fn call(&self, rule: &AnalysisRule, param: Value) -> Result<Value, String> {
+        // 规则由消息触发，仅允许调用低权限方法
+        if !self.functions.iter().any(|function| {
+            function.module == rule.module && function.metadata.name == rule.function
+        }) {
+            return Err(format!("方法不可用: {}.{}", rule.module, rule.function));
+        }
+        let module = self
+            .modules
+            .get(&rule.module)
+            .and_then(Weak::upgrade)
+            .ok_or_else(|| format!("模块不存在: {}", rule.module))?;
+        module
+            .read()
+            .await
+            .call(&rule.function, param)
+            .await
+            .map_err(|e| format!("方法调用失败: {}", e))
+    }
+}
+
+/// 检查规则中的正则表达式
+pub(crate) fn validate_rules(rules: &[AnalysisRule]) -> Result<(), MessagePoolError> {
+    for rule in rules {
+        compile_regex(rule)?;
+    }
+    Ok(())
+}
+
+fn compile_regex(rule: &AnalysisRule) -> Result<Option<Regex>, MessagePoolError> {
+    rule.matcher
+        .regex
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| {
+            MessagePoolError::InvalidParam(format!("invalid regex in rule {}: {}", rule.name, e))
+        })
+}
+
+/// 规则匹配时的模板变量
+struct TemplateVars<'a> {
+    text: &'a str,
+    scene_id: Uuid,
+    group_id: Uuid,
+    message_id: Uuid,
+    captures: Option<Captures<'a>>,
+}
+
+impl TemplateVars<'_> {
+    fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "text" => Some(self.text.to_string()),
+            "scene_id" => Some(self.scene_id.to_string()),
+            "group_id" => Some(self.group_id.to_string()),
+            "message_id" => Some(self.message_id.to_string()),
+            _ => {
+                let captures = self.captures.as_ref()?;
+                let capture = match key.parse::<usize>() {
+                    Ok(index) => captures.get(index),
+                    Err(_) => captures.name(key),
+                };
+                Some(capture.map_or("", |m| m.as_str()).to_string())
+            }
+        }
+    }
+}
+
+/// 替换参数模板中所有字符串里的 `{{变量}}`
+fn render(template: &Value, vars: &TemplateVars) -> Value {
+    match template {
+        Value::String(s) => Value::String(render_str(s, vars)),
+        Value::Array(items) => Value::Array(items.iter().map(|v| render(v, vars)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render(v, vars)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn render_str(template: &str, vars: &TemplateVars) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let key = after[..end].trim();
+                match vars.get(key) {
+                    Some(value) => output.push_str(&value),
+                    // 未知变量原样保留
+                    None => output.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 按 `.` 分隔的路径读取元数据字段
+fn metadata_field<'a>(metadata: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(metadata, |value, key| value.as_object()?.get(key))
+}
+
+#[async_trait]
+impl Analyzer for RuleAnalyzer {
+    fn name(&self) -> &str {
+        "rule_analysis"
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    async fn analyze(
+        &self,
+        db: &DatabaseConnection,
+        group_id: Uuid,
+    ) -> Result<AnalysisOutcome, MessagePoolError> {
+        if self.rules.is_empty() {
+            return Ok(AnalysisOutcome::Skipped);
+        }
+
+        // 上次执行中已命中规则的消息不再匹配，已命中规则的终止标记仍然生效
+        let mut stop = false;
+        let mut hit_messages = HashSet::new();
+        for record in analysis_result::find_analysis_results_by_group_id(db, group_id).await? {
+            if record.analyzer != self.name() {
+                continue;
+            }
+            let Ok(hit) = serde_json::from_value::<RuleHit>(record.result) else {
+                continue;
+            };
+            stop |= self
+                .rules
+                .iter()
+                .any(|compiled| compiled.rule.name == hit.rule && compiled.rule.stop);
+            hit_messages.insert(hit.message_id);
+        }
+
+        let messages = message::find_message_by_group_id(db, group_id).await?;
+        // 场景名称按需查询并缓存
+        let mut scene_names = HashMap::<Uuid, String>::new();
+
+        for msg in messages {
+            if hit_messages.contains(&msg.id) {
+                continue;
+            }
+            let content: ContentData = serde_json::from_value(msg.content.clone())?;
+            // 多模态消息使用预处理得到的派生文本
+            let text = match &content {
+                ContentData::Text { body } => body.as_str(),
+                _ => msg
+                    .metadata
+                    .get(DERIVED_TEXT_METADATA_KEY)
+                    .and_then(|text| text.as_str())
+                    .unwrap_or_default(),
+            };
+
+            for CompiledRule { rule, regex } in &self.rules {
+                let matcher = &rule.matcher;
+                if let Some(msg_type) = matcher.msg_type
+                    && msg_type.to_msg_type() != msg.msg_type
+                {
+                    continue;
+                }
+                if !matcher.keywords.is_empty()
+                    && !matcher.keywords.iter().any(|k| text.contains(k.as_str()))
+                {
+                    continue;
+                }
+                if !matcher
+                    .metadata
+                    .iter()
+                    .all(|(path, expected)| metadata_field(&msg.metadata, path) == Some(expected))
+                {
+                    continue;
+                }
+                if !matcher.scenes.is_empty() {
+                    let scene_id = msg.scene_id.to_string();
+                    if !matcher.scenes.contains(&scene_id) {
+                        let name = match scene_names.entry(msg.scene_id) {
+                            Entry::Occupied(entry) => entry.into_mut(),
+                            Entry::Vacant(entry) => {
+                                entry.insert(scene::find_scene_by_id(db, msg.scene_id).await?.name)
+                            }
+                        };
+                        if !matcher.scenes.contains(name) {
+                            continue;
+                        }
+                    }
+                }
+                let captures = match regex {
+                    Some(regex) => match regex.captures(text) {
+                        Some(captures) => Some(captures),
+                        None => continue,
+                    },
+                    None => None,
+                };
+
+                tracing::info!(
+                    "Rule {} matched message {} in group_id {}",
+                    rule.name,
+                    msg.id,
+                    group_id
+                );
+                let param = render(
+                    &rule.param,
+                    &TemplateVars {
+                        text,
+                        scene_id: msg.scene_id,
+                        group_id,
+                        message_id: msg.id,
+                        captures,
+                    },
+                );
+                let (result, error) = match self.call(rule, param).await {
+                    Ok(result) => (Some(result), None),
+                    Err(e) => {
+                        tracing::warn!("Rule {} failed in group_id {}: {}", rule.name, group_id, e);
+                        (None, Some(e))
+                    }
+                };
+                let hit = RuleHit {
+                    rule: rule.name.clone(),
+                    message_id: msg.id,
+                    module: rule.module.clone(),
+                    function: rule.function.clone(),
+                    result,
+                    error,
+                };
+                // 调用后立即记录，后续步骤失败重试时不再重复调用
+                analysis_result::insert_analysis_result(
+                    db,
+                    group_id,
+                    self.name(),
+                    serde_json::to_value(&hit)?,
+                )
+                .await?;
+
+                if rule.reply {
+                    let body = match (&hit.result, &hit.error) {
+                        (Some(result), _) => serde_json::to_string_pretty(result)?,
+                        (None, Some(e)) => e.clone(),
+                        (None, None) => String::new(),
+                    };
+                    insert_reply(
+                        db,
+                        self.event_tx.as_ref(),
+                        msg.scene_id,
+                        group_id,
+                        "rule",
+                        ContentData::Text { body },
+                    )
+                    .await?;
+                }
+
+                stop |= rule.stop;
+                hit_messages.insert(msg.id);
+                break;
+            }
+        }
+
+        Ok(if stop {
+            AnalysisOutcome::Stopped
+        } else if hit_messages.is_empty() {
+            AnalysisOutcome::Skipped
+        } else {
+            AnalysisOutcome::Handled
+        })
+    }
+}
@@ -10,15 +10,42 @@ pub enum MessagePoolError {
     #[error(transparent)]
     Store(#[from] nihility_store_operate::error::StoreError),
 
+    #[error(transparent)]
+    Model(#[from] nihility_module_model::error::ModelError),
+
     #[error(transparent)]
     Uuid(#[from] uuid::Error),
 
     #[error(transparent)]
     SerdeJson(#[from] serde_json::error::Error),
 
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+
+    #[error(transparent)]
+    AudioDecode(#[from] symphonia::core::errors::Error),
+
     #[error("Scene not found: {0}")]
     SceneNotFound(Uuid),
 
     #[error("Message not found: {0}")]
     MessageNotFound(Uuid),
+
+    #[error("Invalid param: {0}")]
+    InvalidParam(String),
+
+    #[error("Media error: {0}")]
+    Media(String),
+
+    #[error("Analysis error: {0}")]
+    Analysis(String),
+
+    #[error("Module status error: {0}")]
+    ModuleStatus(String),
 }
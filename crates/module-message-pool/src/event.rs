@@ -0,0 +1,91 @@
+use crate::{ContentData, Role};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// 场景事件通道容量，订阅者处理过慢时会丢弃较早的事件
+pub(crate) const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// 场景事件，消息池状态变化时广播给订阅者
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SceneEvent {
+    /// 消息写入场景
+    MessageAdded {
+        /// 场景 ID
+        scene_id: Uuid,
+        /// 消息组 ID
+        group_id: Uuid,
+        /// 发送者角色
+        role: Role,
+        /// 来源标识
+        source: Option<String>,
+        /// 写入的消息
+        messages: Vec<EventMessage>,
+    },
+    /// 消息组开始分析
+    AnalysisStarted {
+        /// 场景 ID
+        scene_id: Uuid,
+        /// 消息组 ID
+        group_id: Uuid,
+    },
+    /// 消息组分析完成
+    AnalysisFinished {
+        /// 场景 ID
+        scene_id: Uuid,
+        /// 消息组 ID
+        group_id: Uuid,
+    },
+    /// 消息被标记为已处理
+    MessageProcessed {
+        /// 场景 ID
+        scene_id: Uuid,
+        /// 消息 ID 列表
+        message_ids: Vec<Uuid>,
+    },
+    /// 助手回复写入场景
+    ReplyCreated(Reply),
+}
+
+impl SceneEvent {
+    /// 事件所属场景 ID
+    pub fn scene_id(&self) -> Uuid {
+        match self {
+            SceneEvent::MessageAdded { scene_id, .. }
+            | SceneEvent::AnalysisStarted { scene_id, .. }
+            | SceneEvent::AnalysisFinished { scene_id, .. }
+            | SceneEvent::MessageProcessed { scene_id, .. } => *scene_id,
+            SceneEvent::ReplyCreated(reply) => reply.scene_id,
+        }
+    }
+}
+
+/// 事件中携带的消息
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct EventMessage {
+    /// 消息 ID
+    pub id: Uuid,
+    /// 消息内容
+    pub content: ContentData,
+}
+
+/// 回复事件，回复消息写入场景后发送给订阅者
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Reply {
+    /// 回复所在场景 ID
+    pub scene_id: Uuid,
+    /// 回复消息 ID
+    pub message_id: Uuid,
+    /// 被回复的消息组 ID
+    pub reply_to_group_id: Uuid,
+    /// 回复内容
+    pub content: ContentData,
+}
+
+/// 发送场景事件，没有订阅者时忽略
+pub(crate) fn send_event(event_tx: Option<&broadcast::Sender<SceneEvent>>, event: SceneEvent) {
+    if let Some(event_tx) = event_tx {
+        let _ = event_tx.send(event);
+    }
+}
@@ -1,6 +1,14 @@
 pub mod add_message;
+pub mod apply_retention;
+pub mod build_context;
 pub mod get_scene_info;
+pub mod get_scene_stats;
+pub mod get_thread;
+pub mod list_analysis_jobs;
 pub mod process_messages;
+pub mod query_messages;
+pub mod retry_analysis_job;
+pub mod search_messages;
 
 use crate::MessagePool;
 use nihility_module::{BoxStream, Callable, FunctionMetadata, Module};
@@ -16,9 +24,28 @@ impl Callable for MessagePool {
             "add_messages" => Ok(serde_json::to_value(
                 self.add_messages(serde_json::from_value(param)?).await?,
             )?),
+            "build_context" => Ok(serde_json::to_value(
+                self.build_context(serde_json::from_value(param)?).await?,
+            )?),
             "get_scene_info" => Ok(serde_json::to_value(
                 self.get_scene_info(serde_json::from_value(param)?).await?,
             )?),
+            "get_scene_stats" => Ok(serde_json::to_value(
+                self.get_scene_stats(serde_json::from_value(param)?).await?,
+            )?),
+            "get_thread" => Ok(serde_json::to_value(
+                self.get_thread(serde_json::from_value(param)?).await?,
+            )?),
+            "list_analysis_jobs" => Ok(serde_json::to_value(
+                self.list_analysis_jobs(serde_json::from_value(param)?)
+                    .await?,
+            )?),
+            "query_messages" => Ok(serde_json::to_value(
+                self.query_messages(serde_json::from_value(param)?).await?,
+            )?),
+            "search_messages" => Ok(serde_json::to_value(
+                self.search_messages(serde_json::from_value(param)?).await?,
+            )?),
             _ => Err(anyhow::anyhow!("Unsupported func_name: {}", func_name)),
         }
     }
@@ -26,8 +53,16 @@ impl Callable for MessagePool {
     async fn call_mut(&mut self, func_name: &str, param: Value) -> anyhow::Result<Value> {
         debug!(func_name = %func_name, param = ?param, "MessagePool call_mut");
         match func_name {
+            "apply_retention" => Ok(serde_json::to_value(
+                self.apply_retention(serde_json::from_value(param)?).await?,
+            )?),
+            "retry_analysis_job" => Ok(serde_json::to_value(
+                self.retry_analysis_job(serde_json::from_value(param)?)
+                    .await?,
+            )?),
             "process_scene_messages" => Ok(serde_json::to_value(
-                self.process_scene_messages(serde_json::from_value(param)?).await?,
+                self.process_scene_messages(serde_json::from_value(param)?)
+                    .await?,
             )?),
             _ => Err(anyhow::anyhow!("Unsupported func_name: {}", func_name)),
         }
@@ -57,6 +92,13 @@ impl Module for MessagePool {
                 params: serde_json::to_value(schema_for!(add_message::AddMessagesParam))
                     .expect("message pool func add_messages build param"),
             },
+            FunctionMetadata {
+                name: "build_context".to_string(),
+                desc: "根据场景树构建可直接发送给模型的对话上下文".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schema_for!(build_context::BuildContextParam))
+                    .expect("message pool func build_context build param"),
+            },
             FunctionMetadata {
                 name: "get_scene_info".to_string(),
                 desc: "获取场景信息".to_string(),
@@ -64,16 +106,73 @@ impl Module for MessagePool {
                 params: serde_json::to_value(schema_for!(get_scene_info::GetSceneInfoParam))
                     .expect("message pool func get_scene_info build param"),
             },
+            FunctionMetadata {
+                name: "get_scene_stats".to_string(),
+                desc: "统计场景及其全部后代场景的消息数量与处理状态".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schema_for!(get_scene_stats::GetSceneStatsParam))
+                    .expect("message pool func get_scene_stats build param"),
+            },
+            FunctionMetadata {
+                name: "get_thread".to_string(),
+                desc: "获取消息组所在的完整会话，包含原始消息及其全部回复".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schema_for!(get_thread::GetThreadParam))
+                    .expect("message pool func get_thread build param"),
+            },
+            FunctionMetadata {
+                name: "list_analysis_jobs".to_string(),
+                desc: "查询消息分析任务及其状态、尝试次数和错误信息".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schema_for!(
+                    list_analysis_jobs::ListAnalysisJobsParam
+                ))
+                .expect("message pool func list_analysis_jobs build param"),
+            },
+            FunctionMetadata {
+                name: "query_messages".to_string(),
+                desc: "按类型、角色、处理状态和时间范围分页查询场景消息".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schema_for!(query_messages::QueryMessagesParam))
+                    .expect("message pool func query_messages build param"),
+            },
+            FunctionMetadata {
+                name: "search_messages".to_string(),
+                desc: "按语义相似度检索消息，可限定场景及其子场景".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schema_for!(search_messages::SearchMessagesParam))
+                    .expect("message pool func search_messages build param"),
+            },
         ]
     }
 
     fn perm_func(&mut self) -> Vec<FunctionMetadata> {
-        vec![FunctionMetadata {
-            name: "process_scene_messages".to_string(),
-            desc: "处理场景中所有未处理的消息".to_string(),
-            tags: vec![],
-            params: serde_json::to_value(schema_for!(process_messages::ProcessSceneMessagesParam))
+        vec![
+            FunctionMetadata {
+                name: "apply_retention".to_string(),
+                desc: "按场景元数据中的保留策略删除或归档过期消息，支持试运行".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schema_for!(apply_retention::ApplyRetentionParam))
+                    .expect("message pool func apply_retention build param"),
+            },
+            FunctionMetadata {
+                name: "process_scene_messages".to_string(),
+                desc: "处理场景中所有未处理的消息，可包含子场景或整个场景子树".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schema_for!(
+                    process_messages::ProcessSceneMessagesParam
+                ))
                 .expect("message pool func process_scene_messages build param"),
-        }]
+            },
+            FunctionMetadata {
+                name: "retry_analysis_job".to_string(),
+                desc: "立即重试分析任务，已完成的分析器不会重复执行".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schema_for!(
+                    retry_analysis_job::RetryAnalysisJobParam
+                ))
+                .expect("message pool func retry_analysis_job build param"),
+            },
+        ]
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,14 @@
-use crate::{Message, MessagePool, MessagePoolError};
-use nihility_store_operate::message;
-use nihility_store_operate::scene;
+use crate::{ContentData, EventMessage, Message, MessagePool, MessagePoolError, Role, SceneEvent};
+use nihility_module_model::Model;
+use nihility_module_model::func::embedding::EmbedParam;
+use nihility_store_operate::error::StoreError;
+use nihility_store_operate::message::{self, NewMessage};
+use nihility_store_operate::{analysis_job, message_embedding, scene};
 use schemars::JsonSchema;
+use sea_orm::{DatabaseConnection, TransactionTrait};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
 /// 添加消息参数
@@ -12,6 +18,15 @@ pub struct AddMessagesParam {
     pub scene_id: Uuid,
     /// 消息列表
     pub messages: Vec<Message>,
+    /// 发送者角色（默认 user）
+    #[serde(default)]
+    pub role: Role,
+    /// 来源标识，例如设备 ID、用户名或模块名称
+    #[serde(default)]
+    pub source: Option<String>,
+    /// 回复的消息组 ID
+    #[serde(default)]
+    pub reply_to_group_id: Option<Uuid>,
 }
 
 /// 添加消息返回结果
@@ -29,30 +44,96 @@ impl MessagePool {
         let _scene = scene::find_scene_by_id(&self.conn, param.scene_id).await?;
 
         let mut message_ids = Vec::new();
+        let mut event_messages = Vec::new();
+        let mut texts = Vec::new();
 
         let group_id = Uuid::new_v4();
 
+        // 消息与分析任务在同一事务中写入，避免消息留在待处理状态却没有任务
+        let txn = self.conn.begin().await.map_err(StoreError::from)?;
         for msg in &param.messages {
             let content_json = serde_json::to_value(&msg.content)?;
 
             let metadata_json = msg.metadata.clone();
 
             let message = message::insert_message(
-                &self.conn,
-                param.scene_id,
-                msg.content.to_msg_type(),
-                content_json,
-                metadata_json,
-                group_id,
-                false,
+                &txn,
+                NewMessage {
+                    scene_id: param.scene_id,
+                    msg_type: msg.content.to_msg_type(),
+                    role: param.role.to_message_role(),
+                    source: param.source.clone(),
+                    reply_to_group_id: param.reply_to_group_id,
+                    content: content_json,
+                    metadata: metadata_json,
+                    group_id,
+                    is_processed: false,
+                },
             )
             .await?;
 
             message_ids.push(message.id);
+            event_messages.push(EventMessage {
+                id: message.id,
+                content: msg.content.clone(),
+            });
+            if let ContentData::Text { body } = &msg.content {
+                texts.push((message.id, body.clone()));
+            }
         }
 
-        self.trigger_analysis(group_id);
+        analysis_job::insert_analysis_job(&txn, group_id).await?;
+        txn.commit().await.map_err(StoreError::from)?;
+
+        self.send_event(SceneEvent::MessageAdded {
+            scene_id: param.scene_id,
+            group_id,
+            role: param.role,
+            source: param.source.clone(),
+            messages: event_messages,
+        });
+        self.notify_analysis(group_id);
+        self.trigger_summary(param.scene_id);
+
+        // 文本消息向量化不阻塞写入，失败时仅记录日志
+        if let Some(model) = self.model.clone()
+            && !texts.is_empty()
+        {
+            tokio::spawn(embed_messages(
+                self.conn.clone(),
+                model,
+                param.scene_id,
+                texts,
+            ));
+        }
 
         Ok(AddMessagesResult { message_ids })
     }
 }
+
+/// 计算文本消息向量并写入向量索引
+async fn embed_messages(
+    conn: DatabaseConnection,
+    model: Arc<RwLock<Model>>,
+    scene_id: Uuid,
+    texts: Vec<(Uuid, String)>,
+) {
+    let param = EmbedParam {
+        texts: texts.iter().map(|(_, text)| text.clone()).collect(),
+    };
+    let embeddings = match model.read().await.embed(&param).await {
+        Ok(embeddings) => embeddings,
+        Err(e) => {
+            tracing::warn!("Failed to embed messages in scene {}: {}", scene_id, e);
+            return;
+        }
+    };
+    for ((message_id, _), embedding) in texts.iter().zip(embeddings) {
+        if let Err(e) =
+            message_embedding::insert_message_embedding(&conn, *message_id, scene_id, &embedding)
+                .await
+        {
+            tracing::warn!("Failed to store embedding of message {}: {}", message_id, e);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::retention::{self, RetentionReport};
+use crate::{MessagePool, MessagePoolError, scene_metadata};
+use nihility_store_operate::scene;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 执行消息保留策略参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApplyRetentionParam {
+    /// 场景 ID，为空时处理所有设置了保留策略的场景
+    #[serde(default)]
+    pub scene_id: Option<Uuid>,
+    /// 试运行，只返回将被清理的消息数量（默认 true）
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+impl MessagePool {
+    /// 按场景元数据中的保留策略清理过期消息
+    pub async fn apply_retention(
+        &self,
+        param: ApplyRetentionParam,
+    ) -> Result<Vec<RetentionReport>, MessagePoolError> {
+        let Some(scene_id) = param.scene_id else {
+            return retention::apply_all_scenes(&self.conn, &self.config.retention, param.dry_run)
+                .await;
+        };
+        let scene = scene::find_scene_by_id(&self.conn, scene_id).await?;
+        let Some(policy) = scene_metadata(&scene).and_then(|metadata| metadata.retention) else {
+            return Ok(Vec::new());
+        };
+        Ok(retention::apply_scene(
+            &self.conn,
+            &self.config.retention,
+            scene.id,
+            &scene.name,
+            &policy,
+            param.dry_run,
+        )
+        .await?
+        .into_iter()
+        .collect())
+    }
+}
@@ -0,0 +1,243 @@
+use crate::summary::find_latest_summary;
+use crate::{ContentData, MessagePool, MessagePoolError};
+use nihility_module_model::Model;
+use nihility_module_model::func::chat_completion::{ChatMessage, ChatRole};
+use nihility_module_model::func::count_tokens::CountTokensParam;
+use nihility_store_operate::message::{self, MessageRole, MsgType};
+use nihility_store_operate::scene;
+use schemars::JsonSchema;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// 每条对话消息的额外 token 开销（角色标记等）
+const MESSAGE_TOKEN_OVERHEAD: usize = 4;
+
+/// 构建上下文参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BuildContextParam {
+    /// 场景 ID
+    pub scene_id: Uuid,
+    /// 是否包含全部子场景的消息（默认 false）
+    #[serde(default)]
+    pub include_children: bool,
+    /// 最近消息数量（默认 20），未处理消息总是包含在内
+    #[serde(default = "default_recent_limit")]
+    pub recent_limit: u64,
+    /// 最多包含的未处理消息数量（默认 100），超出时保留最新的
+    #[serde(default = "default_unprocessed_limit")]
+    pub unprocessed_limit: u64,
+    /// token 预算（默认 4096），超出时优先丢弃较早的消息
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: usize,
+    /// 附加在场景描述之前的系统提示词
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+}
+
+pub(crate) fn default_recent_limit() -> u64 {
+    20
+}
+
+pub(crate) fn default_unprocessed_limit() -> u64 {
+    100
+}
+
+pub(crate) fn default_max_tokens() -> usize {
+    4096
+}
+
+/// 构建上下文结果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BuildContextResult {
+    /// 可直接发送给模型的对话消息列表
+    pub messages: Vec<ChatMessage>,
+    /// 消息列表的 token 数
+    pub token_count: usize,
+    /// 因超出 token 预算而丢弃的消息数量
+    pub omitted_messages: usize,
+}
+
+impl MessagePool {
+    /// 从场景树构建模型上下文
+    pub async fn build_context(
+        &self,
+        param: BuildContextParam,
+    ) -> Result<BuildContextResult, MessagePoolError> {
+        build_context(&self.conn, self.model.as_ref(), &param).await
+    }
+}
+
+/// 从场景树构建模型上下文
+///
+/// 沿场景向上收集到根场景的描述作为系统消息，再拉取场景（及子场景）中最近和未处理的消息，
+/// 按 token 预算从最新的消息开始保留。场景存在摘要时以摘要代替已被摘要的历史消息
+pub async fn build_context(
+    db: &DatabaseConnection,
+    model: Option<&Arc<RwLock<Model>>>,
+    param: &BuildContextParam,
+) -> Result<BuildContextResult, MessagePoolError> {
+    let ancestors = scene::find_scene_ancestors(db, param.scene_id)
+        .await
+        .map_err(|_| MessagePoolError::SceneNotFound(param.scene_id))?;
+    let path = ancestors
+        .iter()
+        .rev()
+        .map(|scene| {
+            let description = scene
+                .metadata
+                .get("description")
+                .and_then(|description| description.as_str())
+                .filter(|description| !description.is_empty());
+            (scene.name.as_str(), description)
+        })
+        .collect::<Vec<_>>();
+    let summary = find_latest_summary(db, param.scene_id).await?;
+    let system = build_system_prompt(
+        param.system_prompt.as_deref(),
+        &path,
+        summary.as_ref().map(|summary| summary.body.as_str()),
+    );
+    let summarized_until = summary.and_then(|summary| summary.summarized_until);
+
+    let scene_ids = if param.include_children {
+        scene::find_scene_subtree_ids(db, param.scene_id).await?
+    } else {
+        vec![param.scene_id]
+    };
+    let mut messages =
+        message::find_recent_messages_by_scene_ids(db, &scene_ids, param.recent_limit)
+            .await?
+            .into_iter()
+            .chain(
+                message::find_recent_unprocessed_messages_by_scene_ids(
+                    db,
+                    &scene_ids,
+                    param.unprocessed_limit,
+                )
+                .await?,
+            )
+            .map(|msg| (msg.id, msg))
+            .collect::<HashMap<_, _>>()
+            .into_values()
+            // 已被摘要且处理过的消息不再重复放入上下文
+            .filter(|msg| {
+                msg.msg_type != MsgType::Summary
+                    && !(msg.scene_id == param.scene_id
+                        && msg.is_processed
+                        && summarized_until.is_some_and(|until| msg.created_at <= until))
+            })
+            .collect::<Vec<_>>();
+    messages.sort_by_key(|msg| msg.created_at);
+
+    // 子场景消息标注来源场景名称
+    let mut scene_names = HashMap::new();
+    let mut chat_messages = Vec::with_capacity(messages.len());
+    for msg in messages {
+        let content: ContentData = serde_json::from_value(msg.content)?;
+        let mut text = content.to_text(&msg.metadata);
+        if msg.scene_id != param.scene_id {
+            let name = match scene_names.get(&msg.scene_id) {
+                Some(name) => name,
+                None => {
+                    let name = scene::find_scene_by_id(db, msg.scene_id).await?.name;
+                    scene_names.entry(msg.scene_id).or_insert(name)
+                }
+            };
+            text = format!("[{}] {}", name, text);
+        }
+        let role = match msg.role {
+            MessageRole::User | MessageRole::Device => ChatRole::User,
+            MessageRole::Assistant | MessageRole::Tool => ChatRole::Assistant,
+            MessageRole::System => ChatRole::System,
+        };
+        chat_messages.push(ChatMessage::new(role, text));
+    }
+
+    let mut texts = Vec::with_capacity(chat_messages.len() + 1);
+    texts.push(system.clone());
+    texts.extend(chat_messages.iter().map(|msg| msg.content.clone()));
+    let counts = count_tokens(model, texts).await;
+
+    // 系统消息总是保留，其余消息从最新的开始填充预算
+    let mut token_count = counts[0];
+    let mut kept = 0;
+    for count in counts[1..].iter().rev() {
+        if token_count + count > param.max_tokens {
+            break;
+        }
+        token_count += count;
+        kept += 1;
+    }
+    let omitted_messages = chat_messages.len() - kept;
+
+    let mut result = Vec::with_capacity(kept + 1);
+    result.push(ChatMessage::new(ChatRole::System, system));
+    result.extend(chat_messages.into_iter().skip(omitted_messages));
+    Ok(BuildContextResult {
+        messages: result,
+        token_count,
+        omitted_messages,
+    })
+}
+
+/// 构建系统消息，`path` 为从根场景到当前场景的名称与描述
+fn build_system_prompt(
+    system_prompt: Option<&str>,
+    path: &[(&str, Option<&str>)],
+    summary: Option<&str>,
+) -> String {
+    let mut prompt = String::new();
+    if let Some(system_prompt) = system_prompt {
+        let _ = writeln!(prompt, "{}\n", system_prompt);
+    }
+    let names = path.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+    let _ = writeln!(prompt, "当前场景：{}", names.join(" > "));
+    for (name, description) in path {
+        if let Some(description) = description {
+            let _ = writeln!(prompt, "- {}：{}", name, description);
+        }
+    }
+    if let Some(summary) = summary {
+        let _ = writeln!(prompt, "\n历史摘要：\n{}", summary);
+    }
+    prompt.trim_end().to_string()
+}
+
+/// 计算每段文本的 token 数（含消息开销），模型没有可用 tokenizer 时按字符估算
+async fn count_tokens(model: Option<&Arc<RwLock<Model>>>, texts: Vec<String>) -> Vec<usize> {
+    let param = CountTokensParam { texts };
+    if let Some(model) = model {
+        match model.read().await.count_tokens(&param).await {
+            Ok(Some(counts)) if counts.len() == param.texts.len() => {
+                return counts
+                    .into_iter()
+                    .map(|count| count + MESSAGE_TOKEN_OVERHEAD)
+                    .collect();
+            }
+            Ok(_) => {}
+            Err(e) => tracing::debug!("Count tokens by model failed, fallback to estimate: {}", e),
+        }
+    }
+    param
+        .texts
+        .iter()
+        .map(|text| estimate_tokens(text))
+        .collect()
+}
+
+/// 估算 token 数：CJK 字符按 1 个 token，其余字符按 4 个字符 1 个 token
+fn estimate_tokens(text: &str) -> usize {
+    let (cjk, other) = text.chars().fold((0usize, 0usize), |(cjk, other), c| {
+        if c as u32 >= 0x2E80 {
+            (cjk + 1, other)
+        } else {
+            (cjk, other + 1)
+        }
+    });
+    cjk + other.div_ceil(4) + MESSAGE_TOKEN_OVERHEAD
+}
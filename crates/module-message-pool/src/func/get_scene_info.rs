@@ -12,6 +12,9 @@ pub struct GetSceneInfoParam {
     /// 是否包含直接子场景 ID 列表（默认 true）
     #[serde(default = "default_include_children")]
     pub include_children: bool,
+    /// 子场景列表是否包含全部后代场景，按层级顺序排列（默认 false）
+    #[serde(default)]
+    pub recursive: bool,
 }
 
 fn default_include_children() -> bool {
@@ -28,11 +31,22 @@ impl MessagePool {
             .await
             .map_err(|_| MessagePoolError::SceneNotFound(param.scene_id))?;
 
-        let children_ids = if param.include_children {
-            self.fetch_direct_children_ids(param.scene_id).await?
-        } else {
-            vec![]
+        let children_ids = match (param.include_children, param.recursive) {
+            (false, _) => vec![],
+            (true, false) => self.fetch_direct_children_ids(param.scene_id).await?,
+            (true, true) => scene::find_scene_subtree_ids(&self.conn, param.scene_id)
+                .await?
+                .into_iter()
+                .skip(1)
+                .map(|id| id.to_string())
+                .collect(),
         };
+        let ancestor_ids = scene::find_scene_ancestors(&self.conn, param.scene_id)
+            .await?
+            .into_iter()
+            .skip(1)
+            .map(|ancestor| ancestor.id.to_string())
+            .collect();
 
         Ok(SceneInfo {
             id: scene.id.to_string(),
@@ -40,6 +54,7 @@ impl MessagePool {
             parent_id: scene.parent_id.map(|p| p.to_string()),
             metadata: scene.metadata,
             children_ids,
+            ancestor_ids,
         })
     }
 
@@ -0,0 +1,93 @@
+use crate::func::process_messages::MessageStateCounts;
+use crate::{MessagePool, MessagePoolError};
+use nihility_store_operate::{message, scene};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// 获取场景子树消息统计参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetSceneStatsParam {
+    /// 子树根场景 ID
+    pub scene_id: Uuid,
+    /// 最大深度，为空时统计全部后代场景（根场景深度为 0）
+    #[serde(default)]
+    pub max_depth: Option<u32>,
+}
+
+/// 消息数量统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MessageStats {
+    /// 消息总数
+    pub total: usize,
+    /// 未处理的消息数量
+    pub unprocessed: usize,
+    /// 按分析状态的消息数量
+    pub state_counts: MessageStateCounts,
+}
+
+/// 单个场景的消息统计
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SceneStats {
+    /// 场景 ID
+    pub scene_id: Uuid,
+    /// 场景名称
+    pub name: String,
+    /// 父场景 ID
+    pub parent_id: Option<Uuid>,
+    /// 相对根场景的深度
+    pub depth: u32,
+    /// 场景自身的消息统计
+    pub stats: MessageStats,
+}
+
+/// 场景子树消息统计结果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SceneStatsResult {
+    /// 各场景统计，按层级顺序排列
+    pub scenes: Vec<SceneStats>,
+    /// 子树合计
+    pub total: MessageStats,
+}
+
+impl MessagePool {
+    /// 统计场景子树中各场景的消息数量与处理状态
+    pub async fn get_scene_stats(
+        &self,
+        param: GetSceneStatsParam,
+    ) -> Result<SceneStatsResult, MessagePoolError> {
+        let nodes = scene::find_scene_subtree(&self.conn, param.scene_id, param.max_depth).await?;
+        if nodes.is_empty() {
+            return Err(MessagePoolError::SceneNotFound(param.scene_id));
+        }
+        let scene_ids = nodes.iter().map(|node| node.scene.id).collect::<Vec<_>>();
+
+        let mut scene_stats: HashMap<Uuid, MessageStats> = HashMap::new();
+        let mut total = MessageStats::default();
+        for row in message::count_messages_by_scene_ids(&self.conn, &scene_ids).await? {
+            let count = row.count as usize;
+            for stats in [scene_stats.entry(row.scene_id).or_default(), &mut total] {
+                stats.total += count;
+                if !row.is_processed {
+                    stats.unprocessed += count;
+                }
+                stats.state_counts.add(row.status.clone().into(), count);
+            }
+        }
+
+        Ok(SceneStatsResult {
+            scenes: nodes
+                .into_iter()
+                .map(|node| SceneStats {
+                    scene_id: node.scene.id,
+                    name: node.scene.name,
+                    parent_id: node.scene.parent_id,
+                    depth: node.depth,
+                    stats: scene_stats.remove(&node.scene.id).unwrap_or_default(),
+                })
+                .collect(),
+            total,
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{MessagePool, MessagePoolError, MessageRecord};
+use nihility_store_operate::message;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// 获取消息会话参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetThreadParam {
+    /// 会话中任意一个消息组 ID
+    pub group_id: Uuid,
+}
+
+impl MessagePool {
+    /// 获取消息组所在的完整会话
+    ///
+    /// 先沿回复关系找到会话起点，再收集起点及其全部回复，按创建时间排列
+    pub async fn get_thread(
+        &self,
+        param: GetThreadParam,
+    ) -> Result<Vec<MessageRecord>, MessagePoolError> {
+        let mut root = param.group_id;
+        let mut visited = HashSet::from([root]);
+        while let Some(parent) = message::find_message_by_group_id(&self.conn, root)
+            .await?
+            .first()
+            .and_then(|msg| msg.reply_to_group_id)
+            && visited.insert(parent)
+        {
+            root = parent;
+        }
+
+        let mut visited = HashSet::from([root]);
+        let mut queue = vec![root];
+        let mut messages = Vec::new();
+        while let Some(group_id) = queue.pop() {
+            messages.extend(message::find_message_by_group_id(&self.conn, group_id).await?);
+            for reply in message::find_messages_by_reply_to_group_id(&self.conn, group_id).await? {
+                if visited.insert(reply.group_id) {
+                    queue.push(reply.group_id);
+                }
+            }
+        }
+        messages.sort_by_key(|msg| msg.created_at);
+
+        messages.into_iter().map(MessageRecord::try_from).collect()
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{MessagePool, MessagePoolError};
+use nihility_store_operate::analysis_job::{self, AnalysisJobModel, AnalysisJobStatus};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 分析任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisJobState {
+    /// 等待执行
+    Pending,
+    /// 执行中
+    Running,
+    /// 执行成功
+    Succeeded,
+    /// 重试次数耗尽后失败
+    Failed,
+}
+
+impl AnalysisJobState {
+    fn to_job_status(self) -> AnalysisJobStatus {
+        match self {
+            AnalysisJobState::Pending => AnalysisJobStatus::Pending,
+            AnalysisJobState::Running => AnalysisJobStatus::Running,
+            AnalysisJobState::Succeeded => AnalysisJobStatus::Succeeded,
+            AnalysisJobState::Failed => AnalysisJobStatus::Failed,
+        }
+    }
+}
+
+impl From<AnalysisJobStatus> for AnalysisJobState {
+    fn from(status: AnalysisJobStatus) -> Self {
+        match status {
+            AnalysisJobStatus::Pending => AnalysisJobState::Pending,
+            AnalysisJobStatus::Running => AnalysisJobState::Running,
+            AnalysisJobStatus::Succeeded => AnalysisJobState::Succeeded,
+            AnalysisJobStatus::Failed => AnalysisJobState::Failed,
+        }
+    }
+}
+
+/// 分析任务信息
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AnalysisJobInfo {
+    /// 任务 ID
+    pub id: Uuid,
+    /// 消息组 ID
+    pub group_id: Uuid,
+    /// 任务状态
+    pub status: AnalysisJobState,
+    /// 已尝试次数
+    pub attempts: i32,
+    /// 最近一次失败的错误信息
+    pub last_error: Option<String>,
+    /// 已完成的分析器
+    pub completed_analyzers: Vec<String>,
+    /// 下次执行时间（RFC 3339）
+    pub next_run_at: String,
+    /// 创建时间（RFC 3339）
+    pub created_at: String,
+    /// 更新时间（RFC 3339）
+    pub updated_at: String,
+}
+
+impl From<AnalysisJobModel> for AnalysisJobInfo {
+    fn from(job: AnalysisJobModel) -> Self {
+        Self {
+            id: job.id,
+            group_id: job.group_id,
+            status: job.status.into(),
+            attempts: job.attempts,
+            last_error: job.last_error,
+            completed_analyzers: serde_json::from_value(job.completed_analyzers)
+                .unwrap_or_default(),
+            next_run_at: job.next_run_at.to_rfc3339(),
+            created_at: job.created_at.to_rfc3339(),
+            updated_at: job.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// 查询分析任务参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListAnalysisJobsParam {
+    /// 按任务状态过滤
+    #[serde(default)]
+    pub status: Option<AnalysisJobState>,
+    /// 返回数量（默认 50）
+    #[serde(default = "default_list_limit")]
+    pub limit: u64,
+}
+
+fn default_list_limit() -> u64 {
+    50
+}
+
+impl MessagePool {
+    /// 查询分析任务，最新创建的在前
+    pub async fn list_analysis_jobs(
+        &self,
+        param: ListAnalysisJobsParam,
+    ) -> Result<Vec<AnalysisJobInfo>, MessagePoolError> {
+        let jobs = analysis_job::find_analysis_jobs(
+            &self.conn,
+            param.status.map(AnalysisJobState::to_job_status),
+            param.limit,
+        )
+        .await?;
+        Ok(jobs.into_iter().map(AnalysisJobInfo::from).collect())
+    }
+}
@@ -1,8 +1,9 @@
-use crate::{MessagePool, MessagePoolError};
+use crate::{MessagePool, MessagePoolError, MessageState, SceneEvent};
 use nihility_store_operate::message;
 use nihility_store_operate::scene;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// 处理场景消息参数
@@ -13,12 +14,38 @@ pub struct ProcessSceneMessagesParam {
     /// 是否同时处理直接子场景（默认 false）
     #[serde(default = "default_process_children")]
     pub process_children: bool,
+    /// 是否处理全部后代场景（默认 false），启用时忽略 `process_children`
+    #[serde(default)]
+    pub recursive: bool,
 }
 
 fn default_process_children() -> bool {
     false
 }
 
+/// 各处理状态的消息数量
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MessageStateCounts {
+    pub pending: usize,
+    pub analyzing: usize,
+    pub handled: usize,
+    pub ignored: usize,
+    pub failed: usize,
+}
+
+impl MessageStateCounts {
+    pub(crate) fn add(&mut self, state: MessageState, n: usize) {
+        let count = match state {
+            MessageState::Pending => &mut self.pending,
+            MessageState::Analyzing => &mut self.analyzing,
+            MessageState::Handled => &mut self.handled,
+            MessageState::Ignored => &mut self.ignored,
+            MessageState::Failed => &mut self.failed,
+        };
+        *count += n;
+    }
+}
+
 /// 处理结果
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessMessagesResult {
@@ -26,6 +53,8 @@ pub struct ProcessMessagesResult {
     pub processed_count: usize,
     /// 已处理的消息 ID 列表
     pub processed_message_ids: Vec<Uuid>,
+    /// 已处理消息按分析状态的统计
+    pub state_counts: MessageStateCounts,
 }
 
 impl MessagePool {
@@ -34,27 +63,48 @@ impl MessagePool {
         &self,
         param: ProcessSceneMessagesParam,
     ) -> Result<ProcessMessagesResult, MessagePoolError> {
-        // Collect scene IDs to process (self + direct children if requested)
-        let mut scene_ids = vec![param.scene_id];
-        if param.process_children {
-            scene_ids.extend(self.collect_direct_child_scene_ids(param.scene_id).await?);
-        }
+        // Collect scene IDs to process (self + direct children or whole subtree if requested)
+        let scene_ids = if param.recursive {
+            scene::find_scene_subtree_ids(&self.conn, param.scene_id).await?
+        } else {
+            let mut scene_ids = vec![param.scene_id];
+            if param.process_children {
+                scene_ids.extend(self.collect_direct_child_scene_ids(param.scene_id).await?);
+            }
+            scene_ids
+        };
 
         // Find all unprocessed messages in these scenes using store_operate
         let messages =
             message::find_unprocessed_messages_by_scene_ids(&self.conn, &scene_ids).await?;
 
-        let mut processed_ids = Vec::new();
-
-        // Mark each message as processed using store_operate
+        let mut processed_ids = Vec::with_capacity(messages.len());
+        let mut scene_processed_ids: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        let mut state_counts = MessageStateCounts::default();
         for msg in messages {
             processed_ids.push(msg.id);
-            message::update_message_processed(&self.conn, msg.id, true).await?;
+            state_counts.add(msg.status.into(), 1);
+            scene_processed_ids
+                .entry(msg.scene_id)
+                .or_default()
+                .push(msg.id);
+        }
+
+        // 按消息 ID 标记为已处理，分析状态保持不变
+        if !processed_ids.is_empty() {
+            message::update_messages_processed(&self.conn, &processed_ids, true).await?;
+        }
+        for (scene_id, message_ids) in scene_processed_ids {
+            self.send_event(SceneEvent::MessageProcessed {
+                scene_id,
+                message_ids,
+            });
         }
 
         Ok(ProcessMessagesResult {
             processed_count: processed_ids.len(),
             processed_message_ids: processed_ids,
+            state_counts,
         })
     }
 
@@ -0,0 +1,132 @@
+use crate::{MessagePool, MessagePoolError, MessageRecord, MessageState, MessageType, Role};
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use nihility_store_operate::message::{self, MessageCursor, MessageFilter};
+use nihility_store_operate::scene;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 单页最大消息数量
+const MAX_QUERY_LIMIT: u64 = 500;
+
+/// 分页查询消息参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueryMessagesParam {
+    /// 场景 ID
+    pub scene_id: Uuid,
+    /// 是否包含全部子场景的消息（默认 false）
+    #[serde(default)]
+    pub include_children: bool,
+    /// 按消息类型过滤
+    #[serde(default)]
+    pub msg_type: Option<MessageType>,
+    /// 按发送者角色过滤
+    #[serde(default)]
+    pub role: Option<Role>,
+    /// 按是否已处理过滤
+    #[serde(default)]
+    pub is_processed: Option<bool>,
+    /// 按处理状态过滤
+    #[serde(default)]
+    pub status: Option<MessageState>,
+    /// 创建时间下界（RFC 3339，包含）
+    #[serde(default)]
+    pub since: Option<String>,
+    /// 创建时间上界（RFC 3339，不包含）
+    #[serde(default)]
+    pub until: Option<String>,
+    /// 上一页返回的游标，为空时从第一页开始
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// 是否按创建时间正序排列（默认 false，最新的消息在前）
+    #[serde(default)]
+    pub ascending: bool,
+    /// 每页数量（默认 50，最大 500）
+    #[serde(default = "default_query_limit")]
+    pub limit: u64,
+}
+
+fn default_query_limit() -> u64 {
+    50
+}
+
+/// 分页查询消息结果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueryMessagesResult {
+    /// 当前页消息
+    pub messages: Vec<MessageRecord>,
+    /// 下一页游标，没有更多消息时为空
+    pub next_cursor: Option<String>,
+}
+
+impl MessagePool {
+    /// 按条件分页查询场景消息
+    pub async fn query_messages(
+        &self,
+        param: QueryMessagesParam,
+    ) -> Result<QueryMessagesResult, MessagePoolError> {
+        let scene_ids = if param.include_children {
+            scene::find_scene_subtree_ids(&self.conn, param.scene_id).await?
+        } else {
+            vec![
+                scene::find_scene_by_id(&self.conn, param.scene_id)
+                    .await?
+                    .id,
+            ]
+        };
+        let filter = MessageFilter {
+            scene_ids,
+            msg_type: param.msg_type.map(|msg_type| msg_type.to_msg_type()),
+            role: param.role.map(|role| role.to_message_role()),
+            is_processed: param.is_processed,
+            status: param.status.map(|status| status.to_message_status()),
+            since: param.since.as_deref().map(parse_time).transpose()?,
+            until: param.until.as_deref().map(parse_time).transpose()?,
+        };
+        let cursor = param.cursor.as_deref().map(decode_cursor).transpose()?;
+
+        let (messages, next_cursor) = message::find_messages_page(
+            &self.conn,
+            &filter,
+            cursor,
+            param.ascending,
+            param.limit.clamp(1, MAX_QUERY_LIMIT),
+        )
+        .await?;
+        Ok(QueryMessagesResult {
+            messages: messages
+                .into_iter()
+                .map(MessageRecord::try_from)
+                .collect::<Result<_, _>>()?,
+            next_cursor: next_cursor.map(encode_cursor),
+        })
+    }
+}
+
+/// 解析 RFC 3339 时间，统一转换为 UTC 以便与存储值比较
+fn parse_time(time: &str) -> Result<DateTime<FixedOffset>, MessagePoolError> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|time| time.with_timezone(&Utc).fixed_offset())
+        .map_err(|e| MessagePoolError::InvalidParam(format!("invalid time {}: {}", time, e)))
+}
+
+/// 游标格式：`<创建时间>_<消息 ID>`
+fn encode_cursor(cursor: MessageCursor) -> String {
+    format!(
+        "{}_{}",
+        cursor
+            .created_at
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Nanos, true),
+        cursor.id
+    )
+}
+
+fn decode_cursor(cursor: &str) -> Result<MessageCursor, MessagePoolError> {
+    let invalid = || MessagePoolError::InvalidParam(format!("invalid cursor: {}", cursor));
+    let (created_at, id) = cursor.rsplit_once('_').ok_or_else(invalid)?;
+    Ok(MessageCursor {
+        created_at: parse_time(created_at).map_err(|_| invalid())?,
+        id: Uuid::parse_str(id).map_err(|_| invalid())?,
+    })
+}
@@ -0,0 +1,28 @@
+use crate::func::list_analysis_jobs::AnalysisJobInfo;
+use crate::{MessagePool, MessagePoolError};
+use nihility_store_operate::analysis_job;
+use nihility_store_operate::message::{self, MessageStatus};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 重试分析任务参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RetryAnalysisJobParam {
+    /// 任务 ID
+    pub job_id: Uuid,
+}
+
+impl MessagePool {
+    /// 重置任务尝试次数并立即重新执行，已完成的分析器不会重复执行
+    pub async fn retry_analysis_job(
+        &self,
+        param: RetryAnalysisJobParam,
+    ) -> Result<AnalysisJobInfo, MessagePoolError> {
+        let job = analysis_job::retry_analysis_job(&self.conn, param.job_id).await?;
+        message::update_group_status(&self.conn, job.group_id, MessageStatus::Pending, None)
+            .await?;
+        self.notify_analysis(job.group_id);
+        Ok(job.into())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{ContentData, MessagePool, MessagePoolError};
+use nihility_module_model::func::embedding::EmbedParam;
+use nihility_store_operate::{message_embedding, scene};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 语义检索消息参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SearchMessagesParam {
+    /// 检索文本
+    pub query: String,
+    /// 限定检索的场景 ID，包含其全部子场景；为空时检索所有场景
+    #[serde(default)]
+    pub scene_id: Option<Uuid>,
+    /// 返回的最大消息数量（默认 10）
+    #[serde(default = "default_top_k")]
+    pub top_k: u64,
+}
+
+fn default_top_k() -> u64 {
+    10
+}
+
+/// 语义检索命中的消息
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SearchedMessage {
+    /// 消息 ID
+    pub id: Uuid,
+    /// 场景 ID
+    pub scene_id: Uuid,
+    /// 消息组 ID
+    pub group_id: Uuid,
+    /// 消息内容
+    pub content: ContentData,
+    /// 消息元数据
+    pub metadata: serde_json::Value,
+    /// 创建时间（RFC 3339）
+    pub created_at: String,
+    /// 余弦相似度
+    pub score: f32,
+}
+
+impl MessagePool {
+    /// 按语义相似度检索消息，结果按相似度降序排列
+    pub async fn search_messages(
+        &self,
+        param: SearchMessagesParam,
+    ) -> Result<Vec<SearchedMessage>, MessagePoolError> {
+        let model = self
+            .model
+            .as_ref()
+            .ok_or_else(|| MessagePoolError::ModuleStatus("model module not set".to_string()))?;
+        let query = model
+            .read()
+            .await
+            .embed(&EmbedParam {
+                texts: vec![param.query],
+            })
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        let scene_ids = match param.scene_id {
+            Some(scene_id) => {
+                scene::find_scene_by_id(&self.conn, scene_id)
+                    .await
+                    .map_err(|_| MessagePoolError::SceneNotFound(scene_id))?;
+                Some(scene::find_scene_subtree_ids(&self.conn, scene_id).await?)
+            }
+            None => None,
+        };
+
+        let mut result = Vec::new();
+        for scored in message_embedding::search_messages_by_embedding(
+            &self.conn,
+            &query,
+            scene_ids.as_deref(),
+            param.top_k,
+        )
+        .await?
+        {
+            let msg = scored.message;
+            result.push(SearchedMessage {
+                id: msg.id,
+                scene_id: msg.scene_id,
+                group_id: msg.group_id,
+                content: serde_json::from_value(msg.content)?,
+                metadata: msg.metadata,
+                created_at: msg.created_at.to_rfc3339(),
+                score: scored.score,
+            });
+        }
+        Ok(result)
+    }
+}
@@ -1,16 +1,28 @@
 pub mod analysis;
 pub mod error;
+pub mod event;
 pub mod func;
+mod media;
+pub mod retention;
+pub mod summary;
 
-pub use analysis::{analysis_worker, GroupIdTask};
+pub use analysis::{AnalysisContext, GroupIdTask, analysis_worker};
 pub use error::MessagePoolError;
-use std::sync::Arc;
+pub use event::{EventMessage, Reply, SceneEvent};
+pub use retention::retention_worker;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
 use std::time::Duration;
+pub use summary::summary_worker;
 
 use crate::error::*;
+use nihility_module::{FunctionMetadata, Module};
+use nihility_module_model::Model;
+use nihility_module_scene_manager::SceneMetadata;
+use nihility_store_operate::scene::SceneModel;
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{RwLock, broadcast, mpsc};
 use tokio::task::JoinHandle;
 use tracing::{error, info};
 use uuid::Uuid;
@@ -23,6 +35,12 @@ pub enum AnalyzerType {
     CommandAnalysis,
     /// 意图识别器 - 使用提示词进行意图识别
     IntentRecognition,
+    /// 回复生成器 - 使用模型生成回复并写回消息来源场景，默认不启用
+    Responder,
+    /// 规则分析器 - 按配置的关键词/正则等规则调用模块方法
+    RuleAnalysis,
+    /// 多模态预处理器 - 将音频、图片、视频转换为文本写入消息元数据
+    MediaPreprocess,
 }
 
 /// 分析器配置
@@ -36,6 +54,9 @@ pub struct AnalyzerConfig {
     /// 优先级（数值越小越先执行）
     #[serde(default = "default_priority")]
     pub priority: i32,
+    /// 单次分析超时时间（秒），为空时使用任务队列的默认值
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 fn default_enabled() -> bool {
@@ -46,12 +67,320 @@ fn default_priority() -> i32 {
     0
 }
 
+/// 命令别名配置
+/// 例如 `/scene list` 映射到 `embed-scene-manager` 模块的 `list_scenes` 方法
+#[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CommandAlias {
+    /// 别名（不含 `/`，多个单词以空格分隔）
+    pub name: String,
+    /// 目标模块
+    pub module: String,
+    /// 目标方法
+    pub function: String,
+}
+
+fn default_command_aliases() -> Vec<CommandAlias> {
+    vec![
+        CommandAlias {
+            name: "scene list".to_string(),
+            module: "embed-scene-manager".to_string(),
+            function: "list_scenes".to_string(),
+        },
+        CommandAlias {
+            name: "scene get".to_string(),
+            module: "embed-scene-manager".to_string(),
+            function: "get_scene".to_string(),
+        },
+    ]
+}
+
+/// 规则匹配条件，所有设置的条件同时满足时匹配
+#[derive(Clone, Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RuleMatcher {
+    /// 文本需要匹配的正则表达式，捕获组可在参数模板中使用
+    #[serde(default)]
+    pub regex: Option<String>,
+    /// 文本包含其中任意一个关键词
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// 消息类型
+    #[serde(default)]
+    pub msg_type: Option<MessageType>,
+    /// 消息所在场景的 ID 或名称，满足其中任意一个
+    #[serde(default)]
+    pub scenes: Vec<String>,
+    /// 消息元数据字段需要等于的值，键为以 `.` 分隔的字段路径
+    #[serde(default)]
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+/// 分析规则，消息匹配时调用模块方法
+#[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AnalysisRule {
+    /// 规则名称
+    pub name: String,
+    /// 匹配条件
+    #[serde(default)]
+    pub matcher: RuleMatcher,
+    /// 目标模块
+    pub module: String,
+    /// 目标方法
+    pub function: String,
+    /// 参数模板，字符串中的 `{{text}}`、`{{scene_id}}`、`{{group_id}}`、`{{message_id}}`
+    /// 以及正则捕获组 `{{1}}`、`{{name}}` 会被替换
+    #[serde(default)]
+    pub param: serde_json::Value,
+    /// 匹配后是否终止分析链（默认 true）
+    #[serde(default = "default_enabled")]
+    pub stop: bool,
+    /// 是否将调用结果回复到消息所在场景
+    #[serde(default)]
+    pub reply: bool,
+}
+
+/// 场景消息摘要配置
+#[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SummaryConfig {
+    /// 是否启用自动摘要
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 场景中未被摘要的消息达到该数量时触发摘要
+    #[serde(default = "default_summary_threshold")]
+    pub message_threshold: usize,
+    /// 摘要时保留的最近原始消息数量
+    #[serde(default = "default_summary_keep_recent")]
+    pub keep_recent: usize,
+}
+
+fn default_summary_threshold() -> usize {
+    50
+}
+
+fn default_summary_keep_recent() -> usize {
+    10
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            message_threshold: default_summary_threshold(),
+            keep_recent: default_summary_keep_recent(),
+        }
+    }
+}
+
+/// 分析任务队列配置
+#[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AnalysisQueueConfig {
+    /// 同时分析的消息组数量，同一场景的消息组依次分析
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// 分析器默认超时时间（秒）
+    #[serde(default = "default_analyzer_timeout_secs")]
+    pub analyzer_timeout_secs: u64,
+    /// 最大尝试次数，超过后任务标记为失败
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// 重试基础间隔（秒），每次失败后翻倍
+    #[serde(default = "default_retry_base_secs")]
+    pub retry_base_secs: u64,
+    /// 重试最大间隔（秒）
+    #[serde(default = "default_retry_max_secs")]
+    pub retry_max_secs: u64,
+    /// 没有新任务通知时检查任务队列的间隔（秒）
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// 成功任务的保留时间（秒），超过后定期删除，失败任务保留以便手动重试
+    #[serde(default = "default_succeeded_job_retention_secs")]
+    pub succeeded_job_retention_secs: u64,
+}
+
+fn default_concurrency() -> usize {
+    4
+}
+
+fn default_analyzer_timeout_secs() -> u64 {
+    120
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_retry_base_secs() -> u64 {
+    10
+}
+
+fn default_retry_max_secs() -> u64 {
+    3600
+}
+
+fn default_poll_interval_secs() -> u64 {
+    30
+}
+
+fn default_succeeded_job_retention_secs() -> u64 {
+    7 * 24 * 3600
+}
+
+impl Default for AnalysisQueueConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: default_concurrency(),
+            analyzer_timeout_secs: default_analyzer_timeout_secs(),
+            max_attempts: default_max_attempts(),
+            retry_base_secs: default_retry_base_secs(),
+            retry_max_secs: default_retry_max_secs(),
+            poll_interval_secs: default_poll_interval_secs(),
+            succeeded_job_retention_secs: default_succeeded_job_retention_secs(),
+        }
+    }
+}
+
+/// 多模态消息预处理配置
+#[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MediaConfig {
+    /// 媒体数据最大字节数
+    #[serde(default = "default_max_source_bytes")]
+    pub max_source_bytes: usize,
+    /// 下载媒体的超时时间（秒）
+    #[serde(default = "default_fetch_timeout_secs")]
+    pub fetch_timeout_secs: u64,
+    /// 允许下载媒体的主机（包含子域名），为空时不限制
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// 是否允许下载内网、回环及链路本地地址的媒体
+    #[serde(default)]
+    pub allow_private_network: bool,
+    /// 图片描述提示词
+    #[serde(default = "default_caption_prompt")]
+    pub caption_prompt: String,
+    /// ffmpeg 可执行文件路径，用于抽取视频关键帧
+    #[serde(default = "default_ffmpeg_path")]
+    pub ffmpeg_path: String,
+    /// 每个视频最多抽取的关键帧数量
+    #[serde(default = "default_video_max_frames")]
+    pub video_max_frames: usize,
+    /// 相邻两次抽取的关键帧最小间隔（秒）
+    #[serde(default = "default_video_frame_interval_secs")]
+    pub video_frame_interval_secs: f64,
+}
+
+fn default_max_source_bytes() -> usize {
+    50 * 1024 * 1024
+}
+
+fn default_fetch_timeout_secs() -> u64 {
+    30
+}
+
+fn default_caption_prompt() -> String {
+    "请用一两句话描述这张图片的内容，如果图片中有文字请一并给出。".to_string()
+}
+
+fn default_ffmpeg_path() -> String {
+    "ffmpeg".to_string()
+}
+
+fn default_video_max_frames() -> usize {
+    4
+}
+
+fn default_video_frame_interval_secs() -> f64 {
+    5.0
+}
+
+impl Default for MediaConfig {
+    fn default() -> Self {
+        Self {
+            max_source_bytes: default_max_source_bytes(),
+            fetch_timeout_secs: default_fetch_timeout_secs(),
+            allowed_hosts: Vec::new(),
+            allow_private_network: false,
+            caption_prompt: default_caption_prompt(),
+            ffmpeg_path: default_ffmpeg_path(),
+            video_max_frames: default_video_max_frames(),
+            video_frame_interval_secs: default_video_frame_interval_secs(),
+        }
+    }
+}
+
+/// 过期消息的处理方式
+#[derive(Clone, Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionAction {
+    /// 直接删除
+    Delete,
+    /// 导出为 JSONL 归档文件后删除
+    #[default]
+    Archive,
+}
+
+/// 消息保留配置，具体策略在场景元数据的 `retention` 字段中设置
+#[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RetentionConfig {
+    /// 是否启用定期清理（默认 false）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 清理间隔（秒）
+    #[serde(default = "default_retention_interval_secs")]
+    pub interval_secs: u64,
+    /// 过期消息的处理方式
+    #[serde(default)]
+    pub action: RetentionAction,
+    /// 归档目录
+    #[serde(default = "default_archive_dir")]
+    pub archive_dir: String,
+    /// 试运行，定期任务只记录将被清理的消息数量
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_retention_interval_secs() -> u64 {
+    3600
+}
+
+fn default_archive_dir() -> String {
+    "archive".to_string()
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_retention_interval_secs(),
+            action: RetentionAction::default(),
+            archive_dir: default_archive_dir(),
+            dry_run: false,
+        }
+    }
+}
+
 /// 消息池模块配置
 #[derive(Clone, Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct MessagePoolConfig {
     /// 分析器列表
     #[serde(default)]
     pub analyzers: Vec<AnalyzerConfig>,
+    /// 命令别名列表
+    #[serde(default = "default_command_aliases")]
+    pub command_aliases: Vec<CommandAlias>,
+    /// 规则分析器的规则列表，按顺序匹配
+    #[serde(default)]
+    pub rules: Vec<AnalysisRule>,
+    /// 分析任务队列配置
+    #[serde(default)]
+    pub analysis_queue: AnalysisQueueConfig,
+    /// 多模态消息预处理配置
+    #[serde(default)]
+    pub media: MediaConfig,
+    /// 场景消息摘要配置
+    #[serde(default)]
+    pub summary: SummaryConfig,
+    /// 消息保留配置
+    #[serde(default)]
+    pub retention: RetentionConfig,
 }
 
 impl Default for MessagePoolConfig {
@@ -62,17 +391,46 @@ impl Default for MessagePoolConfig {
                     analyzer_type: AnalyzerType::CommandAnalysis,
                     enabled: true,
                     priority: i32::MIN,
+                    timeout_secs: None,
+                },
+                AnalyzerConfig {
+                    analyzer_type: AnalyzerType::MediaPreprocess,
+                    enabled: true,
+                    priority: i32::MIN + 1,
+                    timeout_secs: Some(600),
+                },
+                AnalyzerConfig {
+                    analyzer_type: AnalyzerType::RuleAnalysis,
+                    enabled: true,
+                    priority: i32::MIN + 2,
+                    timeout_secs: None,
                 },
                 AnalyzerConfig {
                     analyzer_type: AnalyzerType::IntentRecognition,
                     enabled: true,
                     priority: 0,
+                    timeout_secs: None,
+                },
+                AnalyzerConfig {
+                    analyzer_type: AnalyzerType::Responder,
+                    enabled: false,
+                    priority: 100,
+                    timeout_secs: None,
                 },
             ],
+            command_aliases: default_command_aliases(),
+            rules: Vec::new(),
+            analysis_queue: AnalysisQueueConfig::default(),
+            media: MediaConfig::default(),
+            summary: SummaryConfig::default(),
+            retention: RetentionConfig::default(),
         }
     }
 }
 
+/// 多模态消息预处理得到的文本（语音识别结果或图片、视频描述）所在的元数据字段
+pub const DERIVED_TEXT_METADATA_KEY: &str = "derived_text";
+
 /// 消息内容枚举
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum ContentData {
@@ -96,6 +454,11 @@ pub enum ContentData {
         /// 视频数据（URL 或 Base64）
         source: String,
     },
+    /// 场景历史消息摘要
+    Summary {
+        /// 摘要内容
+        body: String,
+    },
 }
 
 impl ContentData {
@@ -106,6 +469,138 @@ impl ContentData {
             ContentData::Audio { .. } => nihility_store_operate::message::MsgType::Audio,
             ContentData::Image { .. } => nihility_store_operate::message::MsgType::Image,
             ContentData::Video { .. } => nihility_store_operate::message::MsgType::Video,
+            ContentData::Summary { .. } => nihility_store_operate::message::MsgType::Summary,
+        }
+    }
+
+    /// 消息的文本表示，多模态消息附带预处理得到的派生文本
+    pub fn to_text(&self, metadata: &serde_json::Value) -> String {
+        let label = match self {
+            ContentData::Text { body } | ContentData::Summary { body } => return body.clone(),
+            ContentData::Audio { .. } => "[音频]",
+            ContentData::Image { .. } => "[图片]",
+            ContentData::Video { .. } => "[视频]",
+        };
+        match metadata
+            .get(DERIVED_TEXT_METADATA_KEY)
+            .and_then(|text| text.as_str())
+        {
+            Some(text) => format!("{} {}", label, text),
+            None => label.to_string(),
+        }
+    }
+}
+
+/// 消息类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageType {
+    /// 文本消息
+    Text,
+    /// 音频消息
+    Audio,
+    /// 图片消息
+    Image,
+    /// 视频消息
+    Video,
+    /// 场景历史消息摘要
+    Summary,
+}
+
+impl MessageType {
+    /// 获取对应的 MsgType
+    pub fn to_msg_type(&self) -> nihility_store_operate::message::MsgType {
+        match self {
+            MessageType::Text => nihility_store_operate::message::MsgType::Text,
+            MessageType::Audio => nihility_store_operate::message::MsgType::Audio,
+            MessageType::Image => nihility_store_operate::message::MsgType::Image,
+            MessageType::Video => nihility_store_operate::message::MsgType::Video,
+            MessageType::Summary => nihility_store_operate::message::MsgType::Summary,
+        }
+    }
+}
+
+/// 消息发送者角色
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// 用户
+    #[default]
+    User,
+    /// 助手
+    Assistant,
+    /// 设备
+    Device,
+    /// 系统
+    System,
+    /// 工具
+    Tool,
+}
+
+impl Role {
+    /// 获取对应的 MessageRole
+    pub fn to_message_role(&self) -> nihility_store_operate::message::MessageRole {
+        match self {
+            Role::User => nihility_store_operate::message::MessageRole::User,
+            Role::Assistant => nihility_store_operate::message::MessageRole::Assistant,
+            Role::Device => nihility_store_operate::message::MessageRole::Device,
+            Role::System => nihility_store_operate::message::MessageRole::System,
+            Role::Tool => nihility_store_operate::message::MessageRole::Tool,
+        }
+    }
+}
+
+impl From<nihility_store_operate::message::MessageRole> for Role {
+    fn from(role: nihility_store_operate::message::MessageRole) -> Self {
+        match role {
+            nihility_store_operate::message::MessageRole::User => Role::User,
+            nihility_store_operate::message::MessageRole::Assistant => Role::Assistant,
+            nihility_store_operate::message::MessageRole::Device => Role::Device,
+            nihility_store_operate::message::MessageRole::System => Role::System,
+            nihility_store_operate::message::MessageRole::Tool => Role::Tool,
+        }
+    }
+}
+
+/// 消息处理状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageState {
+    /// 等待分析
+    Pending,
+    /// 分析中
+    Analyzing,
+    /// 已由分析器处理
+    Handled,
+    /// 未经分析直接跳过
+    Ignored,
+    /// 分析失败
+    Failed,
+}
+
+impl MessageState {
+    /// 获取对应的 MessageStatus
+    pub fn to_message_status(&self) -> nihility_store_operate::message::MessageStatus {
+        match self {
+            MessageState::Pending => nihility_store_operate::message::MessageStatus::Pending,
+            MessageState::Analyzing => nihility_store_operate::message::MessageStatus::Analyzing,
+            MessageState::Handled => nihility_store_operate::message::MessageStatus::Handled,
+            MessageState::Ignored => nihility_store_operate::message::MessageStatus::Ignored,
+            MessageState::Failed => nihility_store_operate::message::MessageStatus::Failed,
+        }
+    }
+}
+
+impl From<nihility_store_operate::message::MessageStatus> for MessageState {
+    fn from(status: nihility_store_operate::message::MessageStatus) -> Self {
+        match status {
+            nihility_store_operate::message::MessageStatus::Pending => MessageState::Pending,
+            nihility_store_operate::message::MessageStatus::Analyzing => MessageState::Analyzing,
+            nihility_store_operate::message::MessageStatus::Handled => MessageState::Handled,
+            nihility_store_operate::message::MessageStatus::Ignored => MessageState::Ignored,
+            nihility_store_operate::message::MessageStatus::Failed => MessageState::Failed,
         }
     }
 }
@@ -121,6 +616,59 @@ pub struct Message {
     pub metadata: serde_json::Value,
 }
 
+/// 已存储的消息记录
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MessageRecord {
+    /// 消息 ID
+    pub id: Uuid,
+    /// 场景 ID
+    pub scene_id: Uuid,
+    /// 消息组 ID
+    pub group_id: Uuid,
+    /// 发送者角色
+    pub role: Role,
+    /// 来源标识
+    pub source: Option<String>,
+    /// 回复的消息组 ID
+    pub reply_to_group_id: Option<Uuid>,
+    /// 消息内容
+    pub content: ContentData,
+    /// 消息元数据
+    pub metadata: serde_json::Value,
+    /// 是否已处理
+    pub is_processed: bool,
+    /// 处理状态
+    pub status: MessageState,
+    /// 处理该消息的分析器
+    pub handled_by: Option<String>,
+    /// 处理时间（RFC 3339）
+    pub handled_at: Option<String>,
+    /// 创建时间（RFC 3339）
+    pub created_at: String,
+}
+
+impl TryFrom<nihility_store_operate::message::MessageModel> for MessageRecord {
+    type Error = MessagePoolError;
+
+    fn try_from(msg: nihility_store_operate::message::MessageModel) -> Result<Self> {
+        Ok(Self {
+            id: msg.id,
+            scene_id: msg.scene_id,
+            group_id: msg.group_id,
+            role: msg.role.into(),
+            source: msg.source,
+            reply_to_group_id: msg.reply_to_group_id,
+            content: serde_json::from_value(msg.content)?,
+            metadata: msg.metadata,
+            is_processed: msg.is_processed,
+            status: msg.status.into(),
+            handled_by: msg.handled_by,
+            handled_at: msg.handled_at.map(|handled_at| handled_at.to_rfc3339()),
+            created_at: msg.created_at.to_rfc3339(),
+        })
+    }
+}
+
 /// 场景信息
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct SceneInfo {
@@ -129,13 +677,45 @@ pub struct SceneInfo {
     pub parent_id: Option<String>,
     pub metadata: serde_json::Value,
     pub children_ids: Vec<String>,
+    /// 从父场景到根场景的祖先 ID 列表
+    pub ancestor_ids: Vec<String>,
+}
+
+/// 读取场景元数据，校验失败时记录日志并返回 None
+pub(crate) fn scene_metadata(scene: &SceneModel) -> Option<SceneMetadata> {
+    match SceneMetadata::from_value(&scene.metadata) {
+        Ok(metadata) => Some(metadata),
+        Err(e) => {
+            tracing::warn!("Invalid metadata of scene {}: {}", scene.id, e);
+            None
+        }
+    }
+}
+
+/// 可供分析器使用的模块方法
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailableFunction {
+    /// 方法所属模块
+    pub module: String,
+    /// 方法元数据
+    pub metadata: FunctionMetadata,
 }
 
 /// 消息池主结构
 pub struct MessagePool {
     conn: DatabaseConnection,
+    config: MessagePoolConfig,
     task_tx: mpsc::UnboundedSender<Uuid>,
+    task_rx: Option<mpsc::UnboundedReceiver<Uuid>>,
+    model: Option<Arc<RwLock<Model>>>,
+    available_functions: Vec<AvailableFunction>,
+    modules: HashMap<String, Weak<RwLock<dyn Module + Send + Sync>>>,
     analysis_task: Option<JoinHandle<Result<()>>>,
+    summary_tx: mpsc::UnboundedSender<Uuid>,
+    summary_rx: Option<mpsc::UnboundedReceiver<Uuid>>,
+    summary_task: Option<JoinHandle<Result<()>>>,
+    retention_task: Option<JoinHandle<Result<()>>>,
+    event_tx: broadcast::Sender<SceneEvent>,
 }
 
 impl MessagePool {
@@ -149,21 +729,117 @@ impl MessagePool {
     }
 
     pub async fn init(config: MessagePoolConfig, conn: DatabaseConnection) -> Result<Self> {
+        analysis::validate_rules(&config.rules)?;
         let (task_tx, task_rx) = mpsc::unbounded_channel::<Uuid>();
-        let task = tokio::spawn(analysis_worker(task_rx, config, conn.clone()));
+        let (summary_tx, summary_rx) = mpsc::unbounded_channel::<Uuid>();
+        let (event_tx, _) = broadcast::channel(event::EVENT_CHANNEL_CAPACITY);
         Ok(Self {
             conn,
+            config,
             task_tx,
-            analysis_task: Some(task),
+            task_rx: Some(task_rx),
+            model: None,
+            available_functions: Vec::new(),
+            modules: HashMap::new(),
+            analysis_task: None,
+            summary_tx,
+            summary_rx: Some(summary_rx),
+            summary_task: None,
+            retention_task: None,
+            event_tx,
         })
     }
 
-    /// 触发分析链
-    pub fn trigger_analysis(&self, group_id: Uuid) {
+    /// 设置模型模块引用
+    pub fn set_model(&mut self, model: Arc<RwLock<Model>>) {
+        self.model = Some(model);
+    }
+
+    /// 设置分析器可使用的模块方法列表
+    pub fn set_available_functions(&mut self, functions: Vec<AvailableFunction>) {
+        self.available_functions = functions;
+    }
+
+    /// 设置命令可调用的模块，键为模块类型字符串
+    ///
+    /// 只保存弱引用，模块列表中通常也包含消息池自身
+    pub fn set_modules(&mut self, modules: HashMap<String, Arc<RwLock<dyn Module + Send + Sync>>>) {
+        self.modules = modules
+            .iter()
+            .map(|(name, module)| (name.clone(), Arc::downgrade(module)))
+            .collect();
+    }
+
+    /// 启动分析任务，需要在设置完依赖模块后调用
+    ///
+    /// 分析任务保存在数据库中，启动后恢复未完成的任务并按顺序处理
+    pub fn start_analysis_worker(&mut self) -> Result<()> {
+        let task_rx = self.task_rx.take().ok_or_else(|| {
+            MessagePoolError::ModuleStatus("analysis worker already started".to_string())
+        })?;
+        let context = AnalysisContext {
+            model: self.model.clone(),
+            available_functions: Arc::new(self.available_functions.clone()),
+            modules: Arc::new(self.modules.clone()),
+            event_tx: Some(self.event_tx.clone()),
+        };
+        self.analysis_task = Some(tokio::spawn(analysis_worker(
+            task_rx,
+            self.config.clone(),
+            self.conn.clone(),
+            context,
+        )));
+
+        let summary_rx = self.summary_rx.take().ok_or_else(|| {
+            MessagePoolError::ModuleStatus("summary worker already started".to_string())
+        })?;
+        if self.config.summary.enabled {
+            match self.model.clone() {
+                Some(model) => {
+                    self.summary_task = Some(tokio::spawn(summary_worker(
+                        summary_rx,
+                        self.config.summary.clone(),
+                        self.conn.clone(),
+                        model,
+                    )));
+                }
+                None => error!("model module does not exist for summary worker"),
+            }
+        }
+        if self.config.retention.enabled && self.retention_task.is_none() {
+            self.retention_task = Some(tokio::spawn(retention_worker(
+                self.config.retention.clone(),
+                self.conn.clone(),
+            )));
+        }
+        Ok(())
+    }
+
+    /// 订阅场景事件
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SceneEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// 广播场景事件
+    pub(crate) fn send_event(&self, event: SceneEvent) {
+        event::send_event(Some(&self.event_tx), event);
+    }
+
+    /// 通知分析任务检查任务队列
+    pub(crate) fn notify_analysis(&self, group_id: Uuid) {
         if let Err(e) = self.task_tx.send(group_id) {
             tracing::warn!("Failed to send analysis task: {}", e);
         }
     }
+
+    /// 触发场景摘要检查，摘要未启用时忽略
+    pub fn trigger_summary(&self, scene_id: Uuid) {
+        if self.summary_task.is_some()
+            && let Err(e) = self.summary_tx.send(scene_id)
+        {
+            tracing::warn!("Failed to send summary task: {}", e);
+        }
+    }
 }
 
 pub async fn monitor_task(module: Arc<RwLock<MessagePool>>) {
@@ -184,5 +860,33 @@ pub async fn monitor_task(module: Arc<RwLock<MessagePool>>) {
                 }
             }
         }
+        if let Some(task) = module.summary_task.as_ref()
+            && task.is_finished()
+            && let Some(task) = module.summary_task.take()
+        {
+            match task.await {
+                Ok(Ok(())) => info!("Summary task finished"),
+                Ok(Err(e)) => {
+                    error!("Summary task failed: {}", e);
+                }
+                Err(join_err) => {
+                    error!("Summary task join failed: {}", join_err);
+                }
+            }
+        }
+        if let Some(task) = module.retention_task.as_ref()
+            && task.is_finished()
+            && let Some(task) = module.retention_task.take()
+        {
+            match task.await {
+                Ok(Ok(())) => info!("Retention task finished"),
+                Ok(Err(e)) => {
+                    error!("Retention task failed: {}", e);
+                }
+                Err(join_err) => {
+                    error!("Retention task join failed: {}", join_err);
+                }
+            }
+        }
     }
 }
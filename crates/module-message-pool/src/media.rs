@@ -0,0 +1,387 @@
+use crate::MediaConfig;
+use crate::error::*;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use futures::StreamExt;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use std::io::Cursor;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tokio::process::Command;
+use uuid::Uuid;
+
+/// 语音识别模型需要的采样率
+const SPEECH_SAMPLE_RATE: u32 = 16000;
+
+/// 下载媒体的连接超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 下载媒体时最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 5;
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+/// 媒体下载客户端
+///
+/// 只下载 http(s) 地址，默认拒绝内网、回环及链路本地地址，重定向后的地址同样检查
+pub(crate) struct MediaFetcher {
+    client: reqwest::Client,
+    policy: Arc<HostPolicy>,
+    max_bytes: usize,
+}
+
+impl MediaFetcher {
+    pub(crate) fn new(config: &MediaConfig) -> Result<Self> {
+        let policy = Arc::new(HostPolicy {
+            allowed_hosts: config
+                .allowed_hosts
+                .iter()
+                .map(|host| host.trim().trim_start_matches('.').to_ascii_lowercase())
+                .collect(),
+            allow_private_network: config.allow_private_network,
+        });
+        let redirect_policy = policy.clone();
+        let timeout = Duration::from_secs(config.fetch_timeout_secs.max(1));
+        let mut builder = reqwest::Client::builder()
+            .timeout(timeout)
+            .connect_timeout(timeout.min(CONNECT_TIMEOUT))
+            // 代理会替客户端解析地址，无法检查实际连接的地址
+            .no_proxy()
+            .redirect(Policy::custom(move |attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    return attempt.error("too many redirects");
+                }
+                match redirect_policy.check_url(attempt.url()) {
+                    Ok(()) => attempt.follow(),
+                    Err(e) => attempt.error(e),
+                }
+            }));
+        if !config.allow_private_network {
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+        Ok(Self {
+            client: builder.build()?,
+            policy,
+            max_bytes: config.max_source_bytes,
+        })
+    }
+
+    /// 加载媒体数据，支持 http(s) URL、data URL 以及 Base64
+    pub(crate) async fn load_source(&self, source: &str) -> Result<Vec<u8>> {
+        let source = source.trim();
+        let bytes = if is_url(source) {
+            self.download(source).await?
+        } else {
+            let data = match source.strip_prefix("data:") {
+                Some(rest) => rest.split_once(',').map_or(rest, |(_, data)| data),
+                None => source,
+            };
+            STANDARD.decode(data)?
+        };
+        if bytes.len() > self.max_bytes {
+            return Err(MessagePoolError::Media(format!(
+                "media source exceeds {} bytes",
+                self.max_bytes
+            )));
+        }
+        Ok(bytes)
+    }
+
+    /// 流式下载，超过最大字节数时立即停止
+    async fn download(&self, source: &str) -> Result<Vec<u8>> {
+        let url = reqwest::Url::parse(source)
+            .map_err(|e| MessagePoolError::Media(format!("invalid media url {}: {}", source, e)))?;
+        self.policy
+            .check_url(&url)
+            .map_err(MessagePoolError::Media)?;
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        let exceeded = || {
+            MessagePoolError::Media(format!(
+                "media source exceeds {} bytes: {}",
+                self.max_bytes, source
+            ))
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_bytes as u64)
+        {
+            return Err(exceeded());
+        }
+        let mut bytes = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            if bytes.len() + chunk.len() > self.max_bytes {
+                return Err(exceeded());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+}
+
+/// 媒体地址的主机限制
+struct HostPolicy {
+    allowed_hosts: Vec<String>,
+    allow_private_network: bool,
+}
+
+impl HostPolicy {
+    /// 检查协议与主机，IP 形式的主机同时检查地址范围，域名的地址在解析时检查
+    fn check_url(&self, url: &reqwest::Url) -> core::result::Result<(), String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("unsupported media url scheme: {}", url.scheme()));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("media url without host: {}", url))?
+            .to_ascii_lowercase();
+        if !self.allowed_hosts.is_empty()
+            && !self.allowed_hosts.iter().any(|allowed| {
+                host == *allowed
+                    || host
+                        .strip_suffix(allowed.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            })
+        {
+            return Err(format!("media host not allowed: {}", host));
+        }
+        if !self.allow_private_network
+            && let Ok(ip) = host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+            && is_private_ip(ip)
+        {
+            return Err(format!("media address not allowed: {}", ip));
+        }
+        Ok(())
+    }
+}
+
+/// 丢弃内网地址的 DNS 解析器，防止域名解析到内网地址
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| !is_private_ip(addr.ip()))
+                .collect::<Vec<_>>();
+            if addrs.is_empty() {
+                return Err(format!("no public address for media host: {}", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// 是否为内网、回环、链路本地等非公网地址
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                // 100.64.0.0/10 运营商级 NAT
+                || (a == 100 && b & 0xc0 == 64)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_private_ip(IpAddr::V4(ip)),
+            None => {
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local()
+            }
+        },
+    }
+}
+
+/// 解码音频（wav/mp3/ogg）为 16kHz 单声道 f32 数据
+pub(crate) fn decode_audio(bytes: Vec<u8>) -> Result<Vec<f32>> {
+    let stream = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+    let probed = symphonia::default::get_probe().format(
+        &Hint::new(),
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| MessagePoolError::Media("no audio track found".to_string()))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| MessagePoolError::Media("unknown audio sample rate".to_string()))?;
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // 跳过损坏的数据包
+            Err(SymphoniaError::DecodeError(e)) => {
+                tracing::debug!("Skip undecodable audio packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        // 多声道取平均值混合为单声道
+        samples.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+    }
+    Ok(resample(&samples, sample_rate, SPEECH_SAMPLE_RATE))
+}
+
+/// 线性插值重采样
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let ratio = from as f64 / to as f64;
+    let len = (samples.len() as f64 / ratio) as usize;
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position as usize;
+            let frac = (position - index as f64) as f32;
+            let current = samples[index];
+            let next = samples.get(index + 1).copied().unwrap_or(current);
+            current + (next - current) * frac
+        })
+        .collect()
+}
+
+/// 转换为图片理解接口可用的地址，Base64 数据补全为 data URL
+pub(crate) fn image_url(source: &str, max_bytes: usize) -> Result<String> {
+    let source = source.trim();
+    if is_url(source) || source.starts_with("data:") {
+        return Ok(source.to_string());
+    }
+    let bytes = STANDARD.decode(source)?;
+    if bytes.len() > max_bytes {
+        return Err(MessagePoolError::Media(format!(
+            "media source exceeds {} bytes",
+            max_bytes
+        )));
+    }
+    Ok(to_data_url(&bytes))
+}
+
+fn to_data_url(bytes: &[u8]) -> String {
+    let mime = image::guess_format(bytes)
+        .map(|format| format.to_mime_type())
+        .unwrap_or("image/jpeg");
+    format!("data:{};base64,{}", mime, STANDARD.encode(bytes))
+}
+
+/// 视频关键帧抽取参数
+pub(crate) struct KeyframeOptions<'a> {
+    pub ffmpeg_path: &'a str,
+    pub max_frames: usize,
+    pub interval_secs: f64,
+}
+
+/// 使用 ffmpeg 抽取视频关键帧，返回各帧图片的 data URL
+///
+/// 视频数据写入临时文件后交给 ffmpeg，仅解码关键帧，相邻两帧至少间隔 `interval_secs` 秒
+pub(crate) async fn extract_keyframes(
+    video: &[u8],
+    options: &KeyframeOptions<'_>,
+) -> Result<Vec<String>> {
+    let work_dir = std::env::temp_dir().join(format!("nihility-keyframes-{}", Uuid::new_v4()));
+    tokio::fs::create_dir_all(&work_dir).await?;
+    let result = run_ffmpeg(video, &work_dir, options).await;
+    if let Err(e) = tokio::fs::remove_dir_all(&work_dir).await {
+        tracing::warn!("Failed to remove {}: {}", work_dir.display(), e);
+    }
+    result
+}
+
+async fn run_ffmpeg(
+    video: &[u8],
+    work_dir: &Path,
+    options: &KeyframeOptions<'_>,
+) -> Result<Vec<String>> {
+    let input = work_dir.join("input");
+    tokio::fs::write(&input, video).await?;
+    let filter = format!(
+        "select='isnan(prev_selected_t)+gte(t-prev_selected_t\\,{})'",
+        options.interval_secs
+    );
+    let output = Command::new(options.ffmpeg_path)
+        .args(["-hide_banner", "-loglevel", "error", "-skip_frame", "nokey"])
+        // 只允许读取本地文件，避免视频中的播放列表等引用访问网络
+        .args(["-protocol_whitelist", "file"])
+        .arg("-i")
+        .arg(&input)
+        .args(["-vf", &filter, "-vsync", "vfr", "-frames:v"])
+        .arg(options.max_frames.to_string())
+        .arg(work_dir.join("frame_%03d.jpg"))
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(MessagePoolError::Media(format!(
+            "ffmpeg exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let mut frames = Vec::new();
+    let mut entries = tokio::fs::read_dir(work_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_name().to_string_lossy().starts_with("frame_") {
+            frames.push(entry.path());
+        }
+    }
+    frames.sort();
+    let mut urls = Vec::with_capacity(frames.len());
+    for frame in frames {
+        urls.push(to_data_url(&tokio::fs::read(frame).await?));
+    }
+    Ok(urls)
+}
@@ -0,0 +1,195 @@
+use crate::error::*;
+use crate::summary::find_latest_summary;
+use crate::{MessageRecord, RetentionAction, RetentionConfig, scene_metadata};
+use chrono::{DateTime, FixedOffset, Utc};
+use nihility_store_operate::message::MessageCursor;
+use nihility_store_operate::{message, scene};
+use schemars::JsonSchema;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+use tracing::{error, info};
+use uuid::Uuid;
+
+pub use nihility_module_scene_manager::RetentionPolicy;
+
+/// 每批清理的消息数量
+const RETENTION_BATCH_SIZE: u64 = 500;
+
+/// 场景保留策略执行结果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RetentionReport {
+    /// 场景 ID
+    pub scene_id: Uuid,
+    /// 场景名称
+    pub scene_name: String,
+    /// 过期时间点（RFC 3339），不晚于该时间的已处理消息被清理，同一时间的消息按 ID 截断
+    pub expired_until: String,
+    /// 过期消息数量，试运行时为将被清理的数量
+    pub expired_count: u64,
+    /// 归档文件路径
+    pub archive_path: Option<String>,
+    /// 是否为试运行
+    pub dry_run: bool,
+}
+
+/// 定期执行所有场景的保留策略
+pub async fn retention_worker(config: RetentionConfig, conn: DatabaseConnection) -> Result<()> {
+    info!(
+        "Retention worker started with interval {}s",
+        config.interval_secs
+    );
+    let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+    loop {
+        interval.tick().await;
+        match apply_all_scenes(&conn, &config, config.dry_run).await {
+            Ok(reports) => {
+                for report in reports.iter().filter(|report| report.expired_count > 0) {
+                    info!(
+                        "Retention {} {} messages in scene {} until {}{}",
+                        if report.dry_run {
+                            "would remove"
+                        } else {
+                            "removed"
+                        },
+                        report.expired_count,
+                        report.scene_name,
+                        report.expired_until,
+                        report
+                            .archive_path
+                            .as_ref()
+                            .map(|path| format!(", archived to {}", path))
+                            .unwrap_or_default()
+                    );
+                }
+            }
+            Err(e) => error!("Apply retention failed: {}", e),
+        }
+    }
+}
+
+/// 对所有设置了保留策略的场景执行清理
+pub(crate) async fn apply_all_scenes(
+    db: &DatabaseConnection,
+    config: &RetentionConfig,
+    dry_run: bool,
+) -> Result<Vec<RetentionReport>> {
+    let mut reports = Vec::new();
+    for scene in scene::find_all_scenes(db).await? {
+        if let Some(policy) = scene_metadata(&scene).and_then(|metadata| metadata.retention)
+            && let Some(report) =
+                apply_scene(db, config, scene.id, &scene.name, &policy, dry_run).await?
+        {
+            reports.push(report);
+        }
+    }
+    Ok(reports)
+}
+
+/// 对单个场景执行保留策略，没有需要清理的范围时返回 None
+pub(crate) async fn apply_scene(
+    db: &DatabaseConnection,
+    config: &RetentionConfig,
+    scene_id: Uuid,
+    scene_name: &str,
+    policy: &RetentionPolicy,
+    dry_run: bool,
+) -> Result<Option<RetentionReport>> {
+    let Some(expired_until) = expired_until(db, scene_id, policy).await? else {
+        return Ok(None);
+    };
+    let mut report = RetentionReport {
+        scene_id,
+        scene_name: scene_name.to_string(),
+        expired_until: expired_until.created_at.to_rfc3339(),
+        expired_count: 0,
+        archive_path: None,
+        dry_run,
+    };
+    if dry_run {
+        report.expired_count =
+            message::count_processed_messages_until(db, scene_id, expired_until).await?;
+        return Ok(Some(report));
+    }
+
+    let mut archive = None;
+    loop {
+        let messages = message::find_processed_messages_until(
+            db,
+            scene_id,
+            expired_until,
+            RETENTION_BATCH_SIZE,
+        )
+        .await?;
+        if messages.is_empty() {
+            break;
+        }
+        let message_ids = messages.iter().map(|msg| msg.id).collect::<Vec<_>>();
+        if let RetentionAction::Archive = config.action {
+            let writer = match archive.as_mut() {
+                Some(writer) => writer,
+                None => {
+                    let (path, writer) = create_archive(&config.archive_dir, scene_id)?;
+                    report.archive_path = Some(path);
+                    archive.insert(writer)
+                }
+            };
+            for msg in messages {
+                serde_json::to_writer(&mut *writer, &MessageRecord::try_from(msg)?)?;
+                writer.write_all(b"\n")?;
+            }
+            // 写入成功后再删除，避免归档失败导致消息丢失
+            writer.flush()?;
+        }
+        report.expired_count += message::delete_messages_by_ids(db, &message_ids).await?;
+    }
+    Ok(Some(report))
+}
+
+/// 计算过期位置，取各条件中最晚的位置
+///
+/// 数量条件按创建时间与 ID 截断，创建时间相同的消息不会被多删
+async fn expired_until(
+    db: &DatabaseConnection,
+    scene_id: Uuid,
+    policy: &RetentionPolicy,
+) -> Result<Option<MessageCursor>> {
+    let mut until = None;
+    if let Some(max_age_days) = policy.max_age_days {
+        until = until.max(Some(time_cursor(
+            (Utc::now() - chrono::Duration::days(max_age_days.into())).fixed_offset(),
+        )));
+    }
+    if let Some(max_count) = policy.max_count {
+        until = until.max(message::find_nth_latest_message_cursor(db, scene_id, max_count).await?);
+    }
+    if policy.keep_only_summaries {
+        until = until.max(
+            find_latest_summary(db, scene_id)
+                .await?
+                .and_then(|summary| summary.summarized_until)
+                .map(time_cursor),
+        );
+    }
+    Ok(until)
+}
+
+/// 包含指定时间的全部消息的位置
+fn time_cursor(created_at: DateTime<FixedOffset>) -> MessageCursor {
+    MessageCursor {
+        created_at,
+        id: Uuid::max(),
+    }
+}
+
+/// 创建归档文件：`<归档目录>/<场景 ID>/<时间>.jsonl`
+fn create_archive(archive_dir: &str, scene_id: Uuid) -> Result<(String, BufWriter<File>)> {
+    let dir = Path::new(archive_dir).join(scene_id.to_string());
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.jsonl", Utc::now().format("%Y%m%d%H%M%S%.3f")));
+    let file = File::create_new(&path)?;
+    Ok((path.to_string_lossy().to_string(), BufWriter::new(file)))
+}
@@ -0,0 +1,145 @@
+use crate::error::*;
+use crate::{ContentData, SummaryConfig};
+use chrono::{DateTime, FixedOffset};
+use nihility_module_model::Model;
+use nihility_module_model::func::text_completion::TextCompletionParam;
+use nihility_store_operate::message::{self, MessageRole, MsgType, NewMessage};
+use sea_orm::DatabaseConnection;
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::sync::{RwLock, mpsc};
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// 摘要消息的来源标识
+pub const SUMMARY_SOURCE: &str = "summary";
+
+/// 场景最新摘要
+pub(crate) struct SceneSummary {
+    /// 摘要内容
+    pub body: String,
+    /// 摘要覆盖到的最后一条消息的创建时间
+    pub summarized_until: Option<DateTime<FixedOffset>>,
+}
+
+/// 获取场景最新的摘要
+pub(crate) async fn find_latest_summary(
+    db: &DatabaseConnection,
+    scene_id: Uuid,
+) -> Result<Option<SceneSummary>> {
+    let Some(msg) =
+        message::find_latest_message_by_scene_id_and_type(db, scene_id, MsgType::Summary).await?
+    else {
+        return Ok(None);
+    };
+    let body = match serde_json::from_value(msg.content)? {
+        ContentData::Summary { body } => body,
+        _ => return Ok(None),
+    };
+    let summarized_until = msg
+        .metadata
+        .get("summarized_until")
+        .and_then(|until| until.as_str())
+        .and_then(|until| DateTime::parse_from_rfc3339(until).ok());
+    Ok(Some(SceneSummary {
+        body,
+        summarized_until,
+    }))
+}
+
+/// 摘要任务，接收场景 ID 并在未摘要消息超过阈值时生成滚动摘要
+pub async fn summary_worker(
+    mut scene_rx: mpsc::UnboundedReceiver<Uuid>,
+    config: SummaryConfig,
+    conn: DatabaseConnection,
+    model: Arc<RwLock<Model>>,
+) -> Result<()> {
+    info!(
+        "Summary worker started with threshold {}",
+        config.message_threshold
+    );
+    while let Some(scene_id) = scene_rx.recv().await {
+        if let Err(e) = summarize_scene(&conn, &model, &config, scene_id).await {
+            error!("Summarize scene {} failed: {}", scene_id, e);
+        }
+    }
+    info!("Summary worker stopped");
+    Ok(())
+}
+
+/// 检查场景并生成摘要，摘要写入为场景中的摘要消息
+async fn summarize_scene(
+    db: &DatabaseConnection,
+    model: &Arc<RwLock<Model>>,
+    config: &SummaryConfig,
+    scene_id: Uuid,
+) -> Result<()> {
+    let previous = find_latest_summary(db, scene_id).await?;
+    let since = previous
+        .as_ref()
+        .and_then(|summary| summary.summarized_until);
+    let pending = message::find_messages_by_scene_id_since(db, scene_id, since).await?;
+    if pending.len() < config.message_threshold.max(1) {
+        return Ok(());
+    }
+    let count = pending.len().saturating_sub(config.keep_recent);
+    if count == 0 {
+        return Ok(());
+    }
+    let pending = &pending[..count];
+
+    let mut prompt = String::from(
+        "你是一个对话摘要助手，请将以下场景消息整理为简洁的摘要，保留关键事实、决定和待办事项。\n",
+    );
+    if let Some(previous) = &previous {
+        let _ = writeln!(prompt, "\n已有摘要：\n{}", previous.body);
+    }
+    prompt.push_str("\n新消息：\n");
+    for msg in pending {
+        let content: ContentData = serde_json::from_value(msg.content.clone())?;
+        let text = content.to_text(&msg.metadata);
+        let _ = writeln!(prompt, "{}", text);
+    }
+    prompt.push_str("\n请仅输出合并后的完整摘要。");
+
+    let output = model
+        .read()
+        .await
+        .text_completion(&TextCompletionParam { prompt })
+        .await?;
+    // 去除推理模型输出的思考内容
+    let body = match output.rfind("</think>") {
+        Some(index) => &output[index + "</think>".len()..],
+        None => output.as_str(),
+    }
+    .trim()
+    .to_string();
+    if body.is_empty() {
+        return Err(MessagePoolError::Analysis(
+            "empty summary from model output".to_string(),
+        ));
+    }
+
+    let summarized_until = pending[count - 1].created_at;
+    let content = ContentData::Summary { body };
+    message::insert_message(
+        db,
+        NewMessage {
+            scene_id,
+            msg_type: content.to_msg_type(),
+            role: MessageRole::System,
+            source: Some(SUMMARY_SOURCE.to_string()),
+            reply_to_group_id: None,
+            content: serde_json::to_value(&content)?,
+            metadata: serde_json::json!({
+                "summarized_until": summarized_until.to_rfc3339(),
+                "message_count": count,
+            }),
+            group_id: Uuid::new_v4(),
+            is_processed: true,
+        },
+    )
+    .await?;
+    info!("Summarized {} messages in scene {}", count, scene_id);
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::provider::openai_api::OpenAiApiConfig;
 use crate::provider::sense_voice::{SenseVoiceConfig, SenseVoiceLanguage, SenseVoiceTextNorm};
+use crate::provider::sentence_embedding::SentenceEmbeddingConfig;
 use serde::{Deserialize, Serialize};
 
 /// 模型能力类型枚举
@@ -7,6 +8,10 @@ use serde::{Deserialize, Serialize};
 pub enum ModelCapability {
     /// 文本补全能力
     TextCompletion,
+    /// 对话补全能力
+    ChatCompletion,
+    /// 文本向量化能力
+    Embedding,
     /// 图片理解能力
     ImageUnderstanding,
     /// 语音识别能力
@@ -99,6 +104,8 @@ pub enum ProviderType {
 pub enum EmbedProvider {
     /// SenseVoice 语音识别模型
     SenseVoice(SenseVoiceConfig),
+    /// ONNX 句向量模型，初始化时下载模型文件，默认配置不启用
+    SentenceEmbedding(SentenceEmbeddingConfig),
 }
 
 fn default_weight() -> u32 {
@@ -136,9 +143,14 @@ impl Default for ModelConfig {
                         base_url: "http://127.0.0.1:8000/v1".to_string(),
                         api_key: "test".to_string(),
                         model: "Qwen3.5-9B".to_string(),
+                        tokenizer_path: None,
+                        stream_usage: false,
                     }),
                     weight: 1,
-                    capabilities: vec![ModelCapability::TextCompletion],
+                    capabilities: vec![
+                        ModelCapability::TextCompletion,
+                        ModelCapability::ChatCompletion,
+                    ],
                 },
             ],
             load_balance: Default::default(),
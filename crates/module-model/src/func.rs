@@ -1,4 +1,7 @@
 pub mod adjust_weight;
+pub mod chat_completion;
+pub mod count_tokens;
+pub mod embedding;
 pub mod image_understanding;
 pub mod speech_recognition;
 pub mod text_completion;
@@ -10,6 +13,12 @@ use serde_json::Value;
 
 use crate::error::Result as ModuleResult;
 use crate::func::adjust_weight::AdjustWeightParam;
+use crate::func::chat_completion::{
+    ChatCompletionParam, ChatCompletionStreamParam, ChatCompletionWithToolsParam,
+    ChatCompletionWithToolsStreamParam, ChatStreamEvent,
+};
+use crate::func::count_tokens::CountTokensParam;
+use crate::func::embedding::EmbedParam;
 use crate::func::image_understanding::{ImageUnderstandingParam, ImageUnderstandingStreamParam};
 use crate::func::speech_recognition::SpeechRecognitionParam;
 use crate::func::text_completion::{TextCompletionParam, TextCompletionStreamParam};
@@ -25,6 +34,26 @@ impl Callable for Model {
                 let result = self.text_completion(&param).await?;
                 Ok(serde_json::to_value(result)?)
             }
+            "chat_completion" => {
+                let param = serde_json::from_value::<ChatCompletionParam>(param)?;
+                let result = self.chat_completion(&param).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            "chat_completion_with_tools" => {
+                let param = serde_json::from_value::<ChatCompletionWithToolsParam>(param)?;
+                let result = self.chat_completion_with_tools(&param).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            "count_tokens" => {
+                let param = serde_json::from_value::<CountTokensParam>(param)?;
+                let result = self.count_tokens(&param).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            "embed" => {
+                let param = serde_json::from_value::<EmbedParam>(param)?;
+                let result = self.embed(&param).await?;
+                Ok(serde_json::to_value(result)?)
+            }
             "image_understanding" => {
                 let param = serde_json::from_value::<ImageUnderstandingParam>(param)?;
                 let result = self.image_understanding(&param).await?;
@@ -61,6 +90,25 @@ impl Callable for Model {
                 });
                 Ok(Box::pin(mapped_stream))
             }
+            "chat_completion_stream" => {
+                let param = serde_json::from_value::<ChatCompletionStreamParam>(param)?;
+                let stream: ProviderBoxStream<String> = self.chat_completion_stream(&param).await?;
+                let mapped_stream = stream.map(|r: ModuleResult<String>| match r {
+                    Ok(s) => serde_json::to_value(s).map_err(|e| anyhow::anyhow!("{}", e)),
+                    Err(e) => Err(anyhow::anyhow!("{}", e)),
+                });
+                Ok(Box::pin(mapped_stream))
+            }
+            "chat_completion_with_tools_stream" => {
+                let param = serde_json::from_value::<ChatCompletionWithToolsStreamParam>(param)?;
+                let stream: ProviderBoxStream<ChatStreamEvent> =
+                    self.chat_completion_with_tools_stream(&param).await?;
+                let mapped_stream = stream.map(|r: ModuleResult<ChatStreamEvent>| match r {
+                    Ok(event) => serde_json::to_value(event).map_err(|e| anyhow::anyhow!("{}", e)),
+                    Err(e) => Err(anyhow::anyhow!("{}", e)),
+                });
+                Ok(Box::pin(mapped_stream))
+            }
             "image_understanding_stream" => {
                 let param = serde_json::from_value::<ImageUnderstandingStreamParam>(param)?;
                 let stream: ProviderBoxStream<String> =
@@ -81,7 +129,7 @@ impl Callable for Model {
 
 impl Module for Model {
     fn description(&self) -> &str {
-        "AI 模型调用模块，支持文本补全、图片理解、语音识别等接口"
+        "AI 模型调用模块，支持文本补全、对话补全、文本向量化、图片理解、语音识别等接口"
     }
 
     fn no_perm_func(&self) -> Vec<FunctionMetadata> {
@@ -93,6 +141,34 @@ impl Module for Model {
                 params: serde_json::to_value(schemars::schema_for!(TextCompletionParam))
                     .expect("model module func text_completion build param"),
             },
+            FunctionMetadata {
+                name: "chat_completion".to_string(),
+                desc: "对话补全，支持 system/user/assistant 多轮消息".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schemars::schema_for!(ChatCompletionParam))
+                    .expect("model module func chat_completion build param"),
+            },
+            FunctionMetadata {
+                name: "chat_completion_with_tools".to_string(),
+                desc: "对话补全，支持传入工具列表并返回结构化的工具调用".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schemars::schema_for!(ChatCompletionWithToolsParam))
+                    .expect("model module func chat_completion_with_tools build param"),
+            },
+            FunctionMetadata {
+                name: "count_tokens".to_string(),
+                desc: "使用对话模型的 tokenizer 计算文本 token 数".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schemars::schema_for!(CountTokensParam))
+                    .expect("model module func count_tokens build param"),
+            },
+            FunctionMetadata {
+                name: "embed".to_string(),
+                desc: "文本向量化".to_string(),
+                tags: vec![],
+                params: serde_json::to_value(schemars::schema_for!(EmbedParam))
+                    .expect("model module func embed build param"),
+            },
             FunctionMetadata {
                 name: "image_understanding".to_string(),
                 desc: "图片理解".to_string(),
@@ -114,6 +190,23 @@ impl Module for Model {
                 params: serde_json::to_value(schemars::schema_for!(TextCompletionStreamParam))
                     .expect("model module func text_completion_stream build param"),
             },
+            FunctionMetadata {
+                name: "chat_completion_stream".to_string(),
+                desc: "对话补全（流式响应）".to_string(),
+                tags: vec!["streaming".to_string()],
+                params: serde_json::to_value(schemars::schema_for!(ChatCompletionStreamParam))
+                    .expect("model module func chat_completion_stream build param"),
+            },
+            FunctionMetadata {
+                name: "chat_completion_with_tools_stream".to_string(),
+                desc: "对话补全（流式响应），返回文本、推理、工具调用增量及结束原因、用量事件"
+                    .to_string(),
+                tags: vec!["streaming".to_string()],
+                params: serde_json::to_value(schemars::schema_for!(
+                    ChatCompletionWithToolsStreamParam
+                ))
+                .expect("model module func chat_completion_with_tools_stream build param"),
+            },
             FunctionMetadata {
                 name: "image_understanding_stream".to_string(),
                 desc: "图片理解（流式响应）".to_string(),
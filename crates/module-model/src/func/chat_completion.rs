@@ -0,0 +1,246 @@
+use crate::Model;
+use crate::config::ModelCapability;
+use crate::error::Result;
+use crate::provider::BoxStream;
+use futures::StreamExt;
+use nihility_module::FunctionMetadata;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 对话消息角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatRole {
+    /// 系统提示
+    System,
+    /// 用户
+    User,
+    /// 助手
+    Assistant,
+    /// 工具调用结果
+    Tool,
+}
+
+/// 对话消息
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatMessage {
+    /// 消息角色
+    pub role: ChatRole,
+    /// 消息内容
+    pub content: String,
+    /// 助手消息发起的工具调用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ChatToolCall>,
+    /// 工具消息对应的工具调用 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    /// 创建普通文本消息
+    pub fn new(role: ChatRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+}
+
+/// 可供模型调用的工具
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatTool {
+    /// 工具名称
+    pub name: String,
+    /// 工具描述
+    pub description: String,
+    /// 参数 JSON Schema
+    pub parameters: Value,
+}
+
+impl From<FunctionMetadata> for ChatTool {
+    fn from(metadata: FunctionMetadata) -> Self {
+        Self {
+            name: metadata.name,
+            description: metadata.desc,
+            parameters: metadata.params,
+        }
+    }
+}
+
+/// 模型返回的工具调用
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatToolCall {
+    /// 工具调用 ID
+    pub id: String,
+    /// 工具名称
+    pub name: String,
+    /// 调用参数（JSON 字符串）
+    pub arguments: String,
+}
+
+/// 生成结束原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatFinishReason {
+    /// 正常结束
+    Stop,
+    /// 达到最大长度
+    Length,
+    /// 模型发起了工具调用
+    ToolCalls,
+    /// 内容被过滤
+    ContentFilter,
+}
+
+/// Token 用量
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ChatUsage {
+    /// 提示词 token 数
+    pub prompt_tokens: u32,
+    /// 生成 token 数
+    pub completion_tokens: u32,
+    /// 总 token 数
+    pub total_tokens: u32,
+}
+
+/// 对话补全完整响应
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ChatCompletionResponse {
+    /// 文本内容
+    pub content: Option<String>,
+    /// 推理内容
+    pub reasoning_content: Option<String>,
+    /// 工具调用列表
+    pub tool_calls: Vec<ChatToolCall>,
+    /// 结束原因
+    pub finish_reason: Option<ChatFinishReason>,
+    /// Token 用量
+    pub usage: Option<ChatUsage>,
+}
+
+/// 对话补全流式事件
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatStreamEvent {
+    /// 文本增量
+    TextDelta { content: String },
+    /// 推理内容增量
+    ReasoningDelta { content: String },
+    /// 工具调用增量，同一 index 的参数片段需按顺序拼接
+    ToolCallDelta {
+        index: u32,
+        id: Option<String>,
+        name: Option<String>,
+        arguments: Option<String>,
+    },
+    /// 结束原因
+    FinishReason { reason: ChatFinishReason },
+    /// Token 用量
+    Usage(ChatUsage),
+}
+
+/// 对话补全请求参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatCompletionParam {
+    /// 对话消息列表
+    pub messages: Vec<ChatMessage>,
+    /// 优先使用的模型名称，不可用时按负载均衡选择其他模型
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// 对话补全流式请求参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatCompletionStreamParam {
+    /// 对话消息列表
+    pub messages: Vec<ChatMessage>,
+}
+
+/// 带工具的对话补全请求参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatCompletionWithToolsParam {
+    /// 对话消息列表
+    pub messages: Vec<ChatMessage>,
+    /// 可调用的工具列表
+    #[serde(default)]
+    pub tools: Vec<ChatTool>,
+}
+
+/// 带工具的对话补全流式请求参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatCompletionWithToolsStreamParam {
+    /// 对话消息列表
+    pub messages: Vec<ChatMessage>,
+    /// 可调用的工具列表
+    #[serde(default)]
+    pub tools: Vec<ChatTool>,
+}
+
+impl Model {
+    /// 对话补全
+    pub async fn chat_completion(&self, param: &ChatCompletionParam) -> Result<String> {
+        self.pool
+            .invoke_preferred(
+                ModelCapability::ChatCompletion,
+                param.model.as_deref(),
+                |provider| async move { provider.chat_completion(&param.messages).await },
+            )
+            .await
+    }
+
+    /// 对话补全流式响应，仅返回文本增量
+    pub async fn chat_completion_stream(
+        &self,
+        param: &ChatCompletionStreamParam,
+    ) -> Result<BoxStream<String>> {
+        let stream = self
+            .pool
+            .invoke(ModelCapability::ChatCompletion, |provider| async move {
+                provider.chat_completion_stream(&param.messages).await
+            })
+            .await?;
+        Ok(text_deltas(stream))
+    }
+
+    /// 带工具的对话补全
+    pub async fn chat_completion_with_tools(
+        &self,
+        param: &ChatCompletionWithToolsParam,
+    ) -> Result<ChatCompletionResponse> {
+        self.pool
+            .invoke(ModelCapability::ChatCompletion, |provider| async move {
+                provider
+                    .chat_completion_with_tools(&param.messages, &param.tools)
+                    .await
+            })
+            .await
+    }
+
+    /// 带工具的对话补全流式响应，返回完整的类型化事件
+    pub async fn chat_completion_with_tools_stream(
+        &self,
+        param: &ChatCompletionWithToolsStreamParam,
+    ) -> Result<BoxStream<ChatStreamEvent>> {
+        self.pool
+            .invoke(ModelCapability::ChatCompletion, |provider| async move {
+                provider
+                    .chat_completion_with_tools_stream(&param.messages, &param.tools)
+                    .await
+            })
+            .await
+    }
+}
+
+/// 从流式事件中提取文本增量
+pub(crate) fn text_deltas(stream: BoxStream<ChatStreamEvent>) -> BoxStream<String> {
+    Box::pin(stream.filter_map(|event| async move {
+        match event {
+            Ok(ChatStreamEvent::TextDelta { content }) => Some(Ok(content)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }))
+}
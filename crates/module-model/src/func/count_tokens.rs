@@ -0,0 +1,23 @@
+use crate::Model;
+use crate::config::ModelCapability;
+use crate::error::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Token 计数请求参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CountTokensParam {
+    /// 待计数的文本列表
+    pub texts: Vec<String>,
+}
+
+impl Model {
+    /// 使用对话模型的 tokenizer 计算 token 数，未配置 tokenizer 时返回 `None`
+    pub async fn count_tokens(&self, param: &CountTokensParam) -> Result<Option<Vec<usize>>> {
+        self.pool
+            .invoke(ModelCapability::ChatCompletion, |provider| async move {
+                provider.count_tokens(&param.texts).await
+            })
+            .await
+    }
+}
@@ -0,0 +1,23 @@
+use crate::Model;
+use crate::config::ModelCapability;
+use crate::error::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 文本向量化请求参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EmbedParam {
+    /// 待向量化的文本列表
+    pub texts: Vec<String>,
+}
+
+impl Model {
+    /// 文本向量化，返回与输入顺序一致的向量列表
+    pub async fn embed(&self, param: &EmbedParam) -> Result<Vec<Vec<f32>>> {
+        self.pool
+            .invoke(ModelCapability::Embedding, |provider| async move {
+                provider.embed(&param.texts).await
+            })
+            .await
+    }
+}
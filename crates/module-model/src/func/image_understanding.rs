@@ -1,5 +1,6 @@
 use crate::config::ModelCapability;
 use crate::error::Result;
+use crate::func::chat_completion::text_deltas;
 use crate::provider::BoxStream;
 use crate::Model;
 use schemars::JsonSchema;
@@ -40,12 +41,14 @@ impl Model {
         &self,
         param: &ImageUnderstandingStreamParam,
     ) -> Result<BoxStream<String>> {
-        self.pool
+        let stream = self
+            .pool
             .invoke(ModelCapability::ImageUnderstanding, |provider| async move {
                 provider
                     .image_understanding_stream(&param.image_url, &param.prompt)
                     .await
             })
-            .await
+            .await?;
+        Ok(text_deltas(stream))
     }
 }
@@ -162,6 +162,23 @@ impl ModelPool {
     /// 按负载均衡策略选择初始模型，失败时自动尝试下一个有效模型，
     /// 直到所有模型都失败或其中一个成功。
     pub async fn invoke<F, Fut, R>(&self, capability: ModelCapability, f: F) -> Result<R>
+    where
+        F: Fn(Arc<Box<dyn ModelProvider>>) -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        self.invoke_preferred(capability, None, f).await
+    }
+
+    /// 优先使用指定名称的模型调用（支持自动重试）
+    ///
+    /// 指定的模型不存在、不支持该能力或已被禁用时按负载均衡策略选择，
+    /// 失败时同样自动尝试其他有效模型。
+    pub async fn invoke_preferred<F, Fut, R>(
+        &self,
+        capability: ModelCapability,
+        preferred: Option<&str>,
+        f: F,
+    ) -> Result<R>
     where
         F: Fn(Arc<Box<dyn ModelProvider>>) -> Fut,
         Fut: Future<Output = Result<R>>,
@@ -206,6 +223,17 @@ impl ModelPool {
                 }
             }
         };
+        // 指定的模型可用时从该模型开始
+        let start_offset = match preferred {
+            Some(name) => {
+                let models = self.models.read().await;
+                valid_indices
+                    .iter()
+                    .position(|&i| models[i].entry.name == name)
+                    .unwrap_or(start_offset)
+            }
+            None => start_offset,
+        };
 
         // 遍历所有有效模型进行重试
         let mut errors = Vec::new();
@@ -1,5 +1,8 @@
 use crate::config::ProviderType;
 use crate::error::{ModelError, Result};
+use crate::func::chat_completion::{
+    ChatCompletionResponse, ChatMessage, ChatStreamEvent, ChatTool,
+};
 use async_trait::async_trait;
 use futures::Stream;
 use std::pin::Pin;
@@ -7,6 +10,7 @@ use tracing::debug;
 
 pub(crate) mod openai_api;
 pub(crate) mod sense_voice;
+pub(crate) mod sentence_embedding;
 
 pub type BoxStream<T> = Pin<Box<dyn Stream<Item = Result<T>> + Send + 'static>>;
 
@@ -29,6 +33,57 @@ pub trait ModelProvider: Send + Sync {
         ))
     }
 
+    /// 对话补全
+    async fn chat_completion(&self, messages: &[ChatMessage]) -> Result<String> {
+        debug!("chat_completion: messages_len: {}", messages.len());
+        Err(ModelError::Unsupported(
+            "chat_completion is not supported".to_string(),
+        ))
+    }
+
+    /// 对话补全流式响应
+    async fn chat_completion_stream(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<BoxStream<ChatStreamEvent>> {
+        debug!("chat_completion_stream: messages_len: {}", messages.len());
+        Err(ModelError::Unsupported(
+            "chat_completion_stream is not supported".to_string(),
+        ))
+    }
+
+    /// 带工具的对话补全
+    async fn chat_completion_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ChatTool],
+    ) -> Result<ChatCompletionResponse> {
+        debug!(
+            "chat_completion_with_tools: messages_len: {}, tools_len: {}",
+            messages.len(),
+            tools.len()
+        );
+        Err(ModelError::Unsupported(
+            "chat_completion_with_tools is not supported".to_string(),
+        ))
+    }
+
+    /// 带工具的对话补全流式响应
+    async fn chat_completion_with_tools_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ChatTool],
+    ) -> Result<BoxStream<ChatStreamEvent>> {
+        debug!(
+            "chat_completion_with_tools_stream: messages_len: {}, tools_len: {}",
+            messages.len(),
+            tools.len()
+        );
+        Err(ModelError::Unsupported(
+            "chat_completion_with_tools_stream is not supported".to_string(),
+        ))
+    }
+
     /// 图片理解
     async fn image_understanding(&self, image_url: &str, prompt: &str) -> Result<String> {
         debug!(
@@ -45,7 +100,7 @@ pub trait ModelProvider: Send + Sync {
         &self,
         image_url: &str,
         prompt: &str,
-    ) -> Result<BoxStream<String>> {
+    ) -> Result<BoxStream<ChatStreamEvent>> {
         debug!(
             "image_understanding_stream: image_url: {}, prompt: {}",
             image_url, prompt
@@ -55,6 +110,20 @@ pub trait ModelProvider: Send + Sync {
         ))
     }
 
+    /// 文本向量化
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        debug!("embed: texts_len: {}", texts.len());
+        Err(ModelError::Unsupported(
+            "embed is not supported".to_string(),
+        ))
+    }
+
+    /// 使用模型的 tokenizer 计算文本 token 数，没有可用 tokenizer 时返回 `None`
+    async fn count_tokens(&self, texts: &[String]) -> Result<Option<Vec<usize>>> {
+        debug!("count_tokens: texts_len: {}", texts.len());
+        Ok(None)
+    }
+
     /// 语音识别
     async fn speech_recognition(&self, audio_data: &[f32]) -> Result<String> {
         debug!("speech_recognition: data_len: {}", audio_data.len());
@@ -77,6 +146,9 @@ impl ProviderFactory {
                 crate::config::EmbedProvider::SenseVoice(cfg) => {
                     Ok(Box::new(sense_voice::SenseVoice::init(cfg.clone()).await?))
                 }
+                crate::config::EmbedProvider::SentenceEmbedding(cfg) => Ok(Box::new(
+                    sentence_embedding::SentenceEmbedding::init(cfg.clone()).await?,
+                )),
             },
         }
     }
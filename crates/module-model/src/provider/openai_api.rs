@@ -1,12 +1,23 @@
 use crate::error::{ModelError, Result};
+use crate::func::chat_completion::{
+    ChatCompletionResponse, ChatFinishReason, ChatMessage, ChatRole, ChatStreamEvent, ChatTool,
+    ChatToolCall, ChatUsage,
+};
 use crate::provider::{BoxStream, ModelProvider};
 use async_openai::config::OpenAIConfig;
 use async_openai::types::audio::{AudioInput, CreateTranscriptionRequestArgs};
 use async_openai::types::chat::{
-    ChatCompletionRequestMessageContentPartImage, ChatCompletionRequestMessageContentPartText,
-    ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs, ImageUrl, Prompt,
+    ChatCompletionMessageToolCall, ChatCompletionMessageToolCallChunk,
+    ChatCompletionMessageToolCalls, ChatCompletionRequestAssistantMessageArgs,
+    ChatCompletionRequestMessage, ChatCompletionRequestMessageContentPartImage,
+    ChatCompletionRequestMessageContentPartText, ChatCompletionRequestSystemMessage,
+    ChatCompletionRequestToolMessageArgs, ChatCompletionRequestUserMessage,
+    ChatCompletionRequestUserMessageArgs, ChatCompletionStreamOptions, ChatCompletionTool,
+    ChatCompletionTools, CompletionUsage, CreateChatCompletionRequest,
+    CreateChatCompletionRequestArgs, FinishReason, FunctionCall, FunctionObject, ImageUrl, Prompt,
 };
 use async_openai::types::completions::CreateCompletionRequestArgs;
+use async_openai::types::embeddings::CreateEmbeddingRequestArgs;
 use async_openai::types::InputSource;
 use async_openai::Client;
 use async_trait::async_trait;
@@ -15,6 +26,7 @@ use hound::{WavSpec, WavWriter};
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
 use std::sync::Arc;
+use tokenizers::Tokenizer;
 use tokio::sync::mpsc;
 
 /// OpenAI API 提供者配置
@@ -26,12 +38,20 @@ pub struct OpenAiApiConfig {
     pub api_key: String,
     /// 模型名称
     pub model: String,
+    /// 模型 tokenizer 文件路径，用于计算 token 数
+    #[serde(default)]
+    pub tokenizer_path: Option<String>,
+    /// 流式对话是否请求返回 token 用量，仅在服务支持 `stream_options` 时开启
+    #[serde(default)]
+    pub stream_usage: bool,
 }
 
 /// OpenAI API Provider 实现
 pub struct OpenAiApiProvider {
     client: Client<Arc<dyn async_openai::config::Config>>,
     model: String,
+    tokenizer: Option<Tokenizer>,
+    stream_usage: bool,
 }
 
 impl OpenAiApiProvider {
@@ -43,10 +63,262 @@ impl OpenAiApiProvider {
         let client =
             Client::with_config(Arc::new(openai_config) as Arc<dyn async_openai::config::Config>);
 
+        let tokenizer = match &config.tokenizer_path {
+            Some(path) => Some(
+                Tokenizer::from_file(path)
+                    .map_err(|e| ModelError::Provider(format!("tokenizer error: {}", e)))?,
+            ),
+            None => None,
+        };
+
         Ok(Self {
             client,
             model: config.model.clone(),
+            tokenizer,
+            stream_usage: config.stream_usage,
+        })
+    }
+}
+
+/// 转换为 OpenAI 对话消息
+fn to_request_messages(messages: &[ChatMessage]) -> Result<Vec<ChatCompletionRequestMessage>> {
+    let mut request_messages = Vec::with_capacity(messages.len());
+    for message in messages {
+        let request_message = match message.role {
+            ChatRole::System => {
+                ChatCompletionRequestSystemMessage::from(message.content.as_str()).into()
+            }
+            ChatRole::User => {
+                ChatCompletionRequestUserMessage::from(message.content.as_str()).into()
+            }
+            ChatRole::Assistant => {
+                let mut args = ChatCompletionRequestAssistantMessageArgs::default();
+                if !message.content.is_empty() {
+                    args.content(message.content.as_str());
+                }
+                if !message.tool_calls.is_empty() {
+                    args.tool_calls(
+                        message
+                            .tool_calls
+                            .iter()
+                            .map(|call| {
+                                ChatCompletionMessageToolCalls::Function(
+                                    ChatCompletionMessageToolCall {
+                                        id: call.id.clone(),
+                                        function: FunctionCall {
+                                            name: call.name.clone(),
+                                            arguments: call.arguments.clone(),
+                                        },
+                                    },
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                    );
+                }
+                args.build()?.into()
+            }
+            ChatRole::Tool => ChatCompletionRequestToolMessageArgs::default()
+                .content(message.content.as_str())
+                .tool_call_id(message.tool_call_id.clone().unwrap_or_default())
+                .build()?
+                .into(),
+        };
+        request_messages.push(request_message);
+    }
+    Ok(request_messages)
+}
+
+/// 转换为 OpenAI 工具定义
+fn to_request_tools(tools: &[ChatTool]) -> Vec<ChatCompletionTools> {
+    tools
+        .iter()
+        .map(|tool| {
+            ChatCompletionTools::Function(ChatCompletionTool {
+                function: FunctionObject {
+                    name: tool.name.clone(),
+                    description: Some(tool.description.clone()),
+                    parameters: Some(tool.parameters.clone()),
+                    strict: None,
+                },
+            })
         })
+        .collect()
+}
+
+fn to_finish_reason(reason: FinishReason) -> ChatFinishReason {
+    match reason {
+        FinishReason::Stop => ChatFinishReason::Stop,
+        FinishReason::Length => ChatFinishReason::Length,
+        FinishReason::ToolCalls | FinishReason::FunctionCall => ChatFinishReason::ToolCalls,
+        FinishReason::ContentFilter => ChatFinishReason::ContentFilter,
+    }
+}
+
+fn to_usage(usage: CompletionUsage) -> ChatUsage {
+    ChatUsage {
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        total_tokens: usage.total_tokens,
+    }
+}
+
+/// 对话补全响应，额外解析本地推理服务返回的 `reasoning_content`
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    choices: Vec<ChatResponseChoice>,
+    usage: Option<CompletionUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseChoice {
+    message: ChatResponseMessage,
+    finish_reason: Option<FinishReason>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    content: Option<String>,
+    #[serde(alias = "reasoning")]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ChatCompletionMessageToolCall>,
+}
+
+/// 对话补全流式响应块
+#[derive(Debug, Deserialize)]
+struct ChatStreamChunk {
+    #[serde(default)]
+    choices: Vec<ChatStreamChoice>,
+    usage: Option<CompletionUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatStreamChoice {
+    delta: ChatStreamDelta,
+    finish_reason: Option<FinishReason>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatStreamDelta {
+    content: Option<String>,
+    #[serde(alias = "reasoning")]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ChatCompletionMessageToolCallChunk>,
+}
+
+impl ChatStreamChunk {
+    /// 拆分为类型化的流式事件
+    fn into_events(self) -> Vec<ChatStreamEvent> {
+        let mut events = Vec::new();
+        for choice in self.choices {
+            let delta = choice.delta;
+            if let Some(content) = delta.reasoning_content
+                && !content.is_empty()
+            {
+                events.push(ChatStreamEvent::ReasoningDelta { content });
+            }
+            if let Some(content) = delta.content
+                && !content.is_empty()
+            {
+                events.push(ChatStreamEvent::TextDelta { content });
+            }
+            for call in delta.tool_calls {
+                let (name, arguments) = match call.function {
+                    Some(function) => (function.name, function.arguments),
+                    None => (None, None),
+                };
+                events.push(ChatStreamEvent::ToolCallDelta {
+                    index: call.index,
+                    id: call.id,
+                    name,
+                    arguments,
+                });
+            }
+            if let Some(reason) = choice.finish_reason {
+                events.push(ChatStreamEvent::FinishReason {
+                    reason: to_finish_reason(reason),
+                });
+            }
+        }
+        if let Some(usage) = self.usage {
+            events.push(ChatStreamEvent::Usage(to_usage(usage)));
+        }
+        events
+    }
+}
+
+impl OpenAiApiProvider {
+    /// 发送非流式对话请求
+    async fn chat(
+        &self,
+        mut request: CreateChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse> {
+        request.stream = Some(false);
+        let response: ChatResponse = self.client.chat().create_byot(request).await?;
+
+        let mut result = ChatCompletionResponse {
+            usage: response.usage.map(to_usage),
+            ..Default::default()
+        };
+        if let Some(choice) = response.choices.into_iter().next() {
+            result.content = choice.message.content;
+            result.reasoning_content = choice.message.reasoning_content;
+            result.tool_calls = choice
+                .message
+                .tool_calls
+                .into_iter()
+                .map(|call| ChatToolCall {
+                    id: call.id,
+                    name: call.function.name,
+                    arguments: call.function.arguments,
+                })
+                .collect();
+            result.finish_reason = choice.finish_reason.map(to_finish_reason);
+        }
+        Ok(result)
+    }
+
+    /// 发送流式对话请求，转换为类型化事件流
+    async fn chat_stream(
+        &self,
+        mut request: CreateChatCompletionRequest,
+    ) -> Result<BoxStream<ChatStreamEvent>> {
+        request.stream = Some(true);
+        if self.stream_usage {
+            request.stream_options = Some(ChatCompletionStreamOptions {
+                include_usage: Some(true),
+                include_obfuscation: None,
+            });
+        }
+        let mut stream = self
+            .client
+            .chat()
+            .create_stream_byot::<_, ChatStreamChunk>(request)
+            .await?;
+
+        let (tx, rx) = mpsc::channel::<Result<ChatStreamEvent>>(32);
+
+        tokio::spawn(async move {
+            while let Some(result) = stream.next().await {
+                match result {
+                    Ok(chunk) => {
+                        for event in chunk.into_events() {
+                            let _ = tx.send(Ok(event)).await;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(ModelError::ApiRequest(e))).await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        let boxed: BoxStream<ChatStreamEvent> =
+            Box::pin(tokio_stream::wrappers::ReceiverStream::new(rx));
+        Ok(boxed)
     }
 }
 
@@ -103,6 +375,57 @@ impl ModelProvider for OpenAiApiProvider {
         Ok(boxed)
     }
 
+    async fn chat_completion(&self, messages: &[ChatMessage]) -> Result<String> {
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(to_request_messages(messages)?)
+            .build()?;
+
+        Ok(self.chat(request).await?.content.unwrap_or_default())
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<BoxStream<ChatStreamEvent>> {
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(to_request_messages(messages)?)
+            .build()?;
+
+        self.chat_stream(request).await
+    }
+
+    async fn chat_completion_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ChatTool],
+    ) -> Result<ChatCompletionResponse> {
+        let mut args = CreateChatCompletionRequestArgs::default();
+        args.model(&self.model)
+            .messages(to_request_messages(messages)?);
+        if !tools.is_empty() {
+            args.tools(to_request_tools(tools));
+        }
+
+        self.chat(args.build()?).await
+    }
+
+    async fn chat_completion_with_tools_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ChatTool],
+    ) -> Result<BoxStream<ChatStreamEvent>> {
+        let mut args = CreateChatCompletionRequestArgs::default();
+        args.model(&self.model)
+            .messages(to_request_messages(messages)?);
+        if !tools.is_empty() {
+            args.tools(to_request_tools(tools));
+        }
+
+        self.chat_stream(args.build()?).await
+    }
+
     async fn image_understanding(&self, image_url: &str, prompt: &str) -> Result<String> {
         let request = CreateChatCompletionRequestArgs::default()
             .model(&self.model)
@@ -137,7 +460,7 @@ impl ModelProvider for OpenAiApiProvider {
         &self,
         image_url: &str,
         prompt: &str,
-    ) -> Result<BoxStream<String>> {
+    ) -> Result<BoxStream<ChatStreamEvent>> {
         let request = CreateChatCompletionRequestArgs::default()
             .model(&self.model)
             .messages([ChatCompletionRequestUserMessageArgs::default()
@@ -151,34 +474,37 @@ impl ModelProvider for OpenAiApiProvider {
                 ])
                 .build()?
                 .into()])
-            .stream(true)
             .build()?;
 
-        let mut stream = self.client.chat().create_stream(request).await?;
+        self.chat_stream(request).await
+    }
 
-        let (tx, rx) = mpsc::channel::<Result<String>>(32);
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let request = CreateEmbeddingRequestArgs::default()
+            .model(&self.model)
+            .input(texts.to_vec())
+            .build()?;
 
-        tokio::spawn(async move {
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok(response) => {
-                        for choice in response.choices {
-                            let delta = choice.delta;
-                            if let Some(content) = delta.content {
-                                let _ = tx.send(Ok(content)).await;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        let _ = tx.send(Err(ModelError::ApiRequest(e))).await;
-                        break;
-                    }
-                }
-            }
-        });
+        let mut response = self.client.embeddings().create(request).await?;
+        response.data.sort_by_key(|embedding| embedding.index);
 
-        let boxed: BoxStream<String> = Box::pin(tokio_stream::wrappers::ReceiverStream::new(rx));
-        Ok(boxed)
+        Ok(response
+            .data
+            .into_iter()
+            .map(|embedding| embedding.embedding)
+            .collect())
+    }
+
+    async fn count_tokens(&self, texts: &[String]) -> Result<Option<Vec<usize>>> {
+        let Some(tokenizer) = &self.tokenizer else {
+            return Ok(None);
+        };
+        let encodings = tokenizer
+            .encode_batch(texts.to_vec(), false)
+            .map_err(|e| ModelError::Provider(format!("tokenizer error: {}", e)))?;
+        Ok(Some(
+            encodings.iter().map(|encoding| encoding.len()).collect(),
+        ))
     }
 
     async fn speech_recognition(&self, audio_data: &[f32]) -> Result<String> {
@@ -0,0 +1,229 @@
+use crate::error::{ModelError, Result};
+use crate::provider::ModelProvider;
+use async_trait::async_trait;
+use ort::session::Session;
+use ort::session::builder::GraphOptimizationLevel;
+use ort::value::{DynTensor, Tensor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+use tokio::sync::Mutex;
+use tracing::info;
+
+/// 句向量池化方式
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub enum SentenceEmbeddingPooling {
+    /// 取 `[CLS]` 位置的向量（BGE 系列模型）
+    Cls,
+    /// 按 attention mask 取平均（Sentence-Transformers 系列模型）
+    Mean,
+}
+
+/// ONNX 句向量模型配置
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SentenceEmbeddingConfig {
+    /// ONNX 模型文件路径
+    pub model_path: String,
+    /// Tokenizer 文件路径
+    pub tokenizer_path: String,
+    /// 模型不存在时下载使用的 HuggingFace 仓库
+    pub repo: String,
+    /// 仓库中的 ONNX 模型文件
+    pub repo_model_file: String,
+    /// 仓库中的 Tokenizer 文件
+    pub repo_tokenizer_file: String,
+    /// 池化方式
+    pub pooling: SentenceEmbeddingPooling,
+    /// 最大 token 长度，超出部分截断
+    pub max_length: usize,
+    /// 是否对输出向量进行 L2 归一化
+    pub normalize: bool,
+}
+
+/// ONNX 句向量模型
+pub struct SentenceEmbedding {
+    pooling: SentenceEmbeddingPooling,
+    normalize: bool,
+    /// 模型是否需要 token_type_ids 输入
+    with_token_type_ids: bool,
+    /// onnx模型session
+    session: Arc<Mutex<Session>>,
+    /// tokenizer
+    tokenizer: Tokenizer,
+}
+
+impl SentenceEmbedding {
+    pub async fn init(config: SentenceEmbeddingConfig) -> Result<Self> {
+        let model_dir = Path::new(&config.model_path)
+            .parent()
+            .ok_or(ModelError::Provider(format!(
+                "SentenceEmbedding invalid model path: {:?}",
+                config.model_path
+            )))?;
+        if !fs::exists(&config.model_path)? {
+            info!(
+                "Download SentenceEmbedding model to directory: {:?}",
+                model_dir
+            );
+            fs::create_dir_all(model_dir)?;
+            let hf_api = hf_hub::api::tokio::ApiBuilder::from_env()
+                .with_cache_dir(model_dir.to_path_buf())
+                .build()?;
+            let repo = hf_api.model(config.repo.clone());
+            repo.download(&config.repo_model_file).await?;
+            repo.download(&config.repo_tokenizer_file).await?;
+        }
+        let session = Session::builder()?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| {
+                ModelError::Provider(format!("SentenceEmbedding Ort Session build fail: {}", e))
+            })?
+            .with_intra_threads(1)
+            .map_err(|e| {
+                ModelError::Provider(format!("SentenceEmbedding Ort Session build fail: {}", e))
+            })?
+            .commit_from_file(&config.model_path)?;
+        let with_token_type_ids = session
+            .inputs()
+            .iter()
+            .any(|input| input.name() == "token_type_ids");
+
+        let mut tokenizer = Tokenizer::from_file(&config.tokenizer_path)
+            .map_err(|e| ModelError::Provider(format!("tokenizer error: {}", e)))?;
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: config.max_length,
+                ..Default::default()
+            }))
+            .map_err(|e| ModelError::Provider(format!("tokenizer error: {}", e)))?;
+        tokenizer.with_padding(Some(PaddingParams::default()));
+
+        Ok(Self {
+            pooling: config.pooling,
+            normalize: config.normalize,
+            with_token_type_ids,
+            session: Arc::new(Mutex::new(session)),
+            tokenizer,
+        })
+    }
+
+    pub async fn infer(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| ModelError::Provider(format!("tokenizer error: {}", e)))?;
+        let batch = encodings.len();
+        let seq_len = encodings[0].len();
+
+        let mut input_ids = Vec::with_capacity(batch * seq_len);
+        let mut attention_mask = Vec::with_capacity(batch * seq_len);
+        let mut token_type_ids = Vec::with_capacity(batch * seq_len);
+        for encoding in &encodings {
+            input_ids.extend(encoding.get_ids().iter().map(|&id| id as i64));
+            attention_mask.extend(encoding.get_attention_mask().iter().map(|&m| m as i64));
+            token_type_ids.extend(encoding.get_type_ids().iter().map(|&t| t as i64));
+        }
+
+        let mut inputs: HashMap<&str, DynTensor> = HashMap::new();
+        inputs.insert(
+            "input_ids",
+            Tensor::from_array(([batch, seq_len], input_ids))?.upcast(),
+        );
+        inputs.insert(
+            "attention_mask",
+            Tensor::from_array(([batch, seq_len], attention_mask.clone()))?.upcast(),
+        );
+        if self.with_token_type_ids {
+            inputs.insert(
+                "token_type_ids",
+                Tensor::from_array(([batch, seq_len], token_type_ids))?.upcast(),
+            );
+        }
+
+        let mut session = self.session.lock().await;
+        let outputs = session.run(inputs)?;
+        let hidden = outputs[0].try_extract_array::<f32>()?;
+
+        let mut embeddings = Vec::with_capacity(batch);
+        match hidden.ndim() {
+            // [batch, hidden]，模型已完成池化
+            2 => {
+                for row in hidden.outer_iter() {
+                    embeddings.push(row.iter().copied().collect::<Vec<f32>>());
+                }
+            }
+            // [batch, seq_len, hidden]
+            3 => {
+                let dim = hidden.shape()[2];
+                for (b, tokens) in hidden.outer_iter().enumerate() {
+                    let embedding = match self.pooling {
+                        SentenceEmbeddingPooling::Cls => tokens
+                            .outer_iter()
+                            .next()
+                            .map_or_else(|| vec![0.0; dim], |cls| cls.iter().copied().collect()),
+                        SentenceEmbeddingPooling::Mean => {
+                            let mask = &attention_mask[b * seq_len..(b + 1) * seq_len];
+                            let mut sum = vec![0.0f32; dim];
+                            let mut count = 0.0f32;
+                            for (token, &m) in tokens.outer_iter().zip(mask) {
+                                if m == 0 {
+                                    continue;
+                                }
+                                count += 1.0;
+                                for (s, v) in sum.iter_mut().zip(token.iter()) {
+                                    *s += v;
+                                }
+                            }
+                            sum.iter().map(|s| s / count.max(1.0)).collect()
+                        }
+                    };
+                    embeddings.push(embedding);
+                }
+            }
+            ndim => {
+                return Err(ModelError::Provider(format!(
+                    "SentenceEmbedding unexpected output dimension: {}",
+                    ndim
+                )));
+            }
+        }
+
+        if self.normalize {
+            for embedding in embeddings.iter_mut() {
+                let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+                if norm > 0.0 {
+                    embedding.iter_mut().for_each(|v| *v /= norm);
+                }
+            }
+        }
+        Ok(embeddings)
+    }
+}
+
+impl Default for SentenceEmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            model_path: "model/bge_small_zh/onnx/model_quantized.onnx".to_string(),
+            tokenizer_path: "model/bge_small_zh/tokenizer.json".to_string(),
+            repo: "Xenova/bge-small-zh-v1.5".to_string(),
+            repo_model_file: "onnx/model_quantized.onnx".to_string(),
+            repo_tokenizer_file: "tokenizer.json".to_string(),
+            pooling: SentenceEmbeddingPooling::Cls,
+            max_length: 512,
+            normalize: true,
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for SentenceEmbedding {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.infer(texts).await
+    }
+}
@@ -0,0 +1,264 @@
+use crate::error::*;
+use nihility_store_operate::message::{MessageRole, MessageStatus, MsgType};
+use nihility_store_operate::{StoreError, module_config};
+use schemars::JsonSchema;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// 当前场景包版本
+pub const SCENE_BUNDLE_VERSION: u32 = 1;
+
+/// 保存设备映射的边缘设备控制模块名称
+pub(crate) const EDGE_DEVICE_CONTROL_MODULE: &str = "nihility-module-edge-device-control";
+
+/// 场景包序列化格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+/// 导入时场景名称或页面路径冲突的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// 追加数字后缀重命名
+    #[default]
+    Rename,
+    /// 复用已存在的同名场景或同路径页面，不修改其内容
+    Reuse,
+    /// 存在冲突时拒绝导入
+    Fail,
+}
+
+/// 可在不同部署间迁移的场景子树
+///
+/// 包内 ID 仅用于表示包内引用关系，导入时全部重新生成
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneBundle {
+    /// 场景包版本
+    pub version: u32,
+    /// 导出时间（RFC 3339）
+    pub exported_at: String,
+    /// 场景列表，按层级顺序排列，第一个为子树根场景
+    pub scenes: Vec<BundleScene>,
+    /// 场景消息
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<BundleMessage>,
+    /// 设备映射引用的网页
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub html_pages: Vec<BundleHtmlPage>,
+    /// 映射到包内场景的设备
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub device_mappings: Vec<BundleDeviceMapping>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleScene {
+    pub id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    #[serde(with = "json_string")]
+    pub metadata: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleMessage {
+    pub scene_id: Uuid,
+    pub group_id: Uuid,
+    pub msg_type: MsgType,
+    pub role: MessageRole,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to_group_id: Option<Uuid>,
+    #[serde(with = "json_string")]
+    pub content: Value,
+    #[serde(with = "json_string")]
+    pub metadata: Value,
+    pub status: MessageStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handled_by: Option<String>,
+    /// 创建时间（RFC 3339）
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleHtmlPage {
+    pub path: String,
+    pub html: String,
+}
+
+/// 边缘设备自动连接配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleDeviceMapping {
+    pub device_id: String,
+    pub mapping_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot_selector: Option<String>,
+    pub scene_id: Uuid,
+}
+
+/// 任意 JSON 值以 JSON 字符串保存，TOML 无法表示其中的 null
+mod json_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    pub fn serialize<S: Serializer>(value: &Value, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        serde_json::from_str(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+impl SceneBundle {
+    pub fn encode(&self, format: BundleFormat) -> Result<String> {
+        Ok(match format {
+            BundleFormat::Json => serde_json::to_string_pretty(self)?,
+            BundleFormat::Toml => toml::to_string_pretty(self)?,
+        })
+    }
+
+    pub fn decode(content: &str, format: BundleFormat) -> Result<Self> {
+        let bundle: Self = match format {
+            BundleFormat::Json => serde_json::from_str(content)?,
+            BundleFormat::Toml => toml::from_str(content)?,
+        };
+        if bundle.version != SCENE_BUNDLE_VERSION {
+            return Err(SceneError::InvalidBundle(format!(
+                "unsupported version {}, expected {}",
+                bundle.version, SCENE_BUNDLE_VERSION
+            )));
+        }
+        Ok(bundle)
+    }
+}
+
+/// 提取映射地址中 `/html/{path}` 引用的网页路径
+pub(crate) fn html_page_path(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once("/html/")?;
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    (!path.is_empty()).then_some(path)
+}
+
+/// 读取边缘设备控制模块保存在数据库中的配置，返回记录 ID 与配置内容
+pub(crate) async fn find_device_config(db: &DatabaseConnection) -> Result<Option<(Uuid, Value)>> {
+    match module_config::find_by_module_name(db, EDGE_DEVICE_CONTROL_MODULE).await {
+        Ok(record) => Ok(Some((record.id, record.config_value))),
+        Err(StoreError::NotFound(_)) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 解析边缘设备控制模块配置中的自动连接设备列表，跳过无法解析的设备
+pub(crate) fn device_mappings(config: &Value) -> Vec<BundleDeviceMapping> {
+    config
+        .get("auto_connect")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|mapping| match serde_json::from_value(mapping.clone()) {
+            Ok(mapping) => Some(mapping),
+            Err(e) => {
+                tracing::warn!("Skip invalid device mapping {}: {}", mapping, e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bundle() -> SceneBundle {
+        let scene_id = Uuid::new_v4();
+        SceneBundle {
+            version: SCENE_BUNDLE_VERSION,
+            exported_at: "2026-10-18T00:00:00+00:00".to_string(),
+            scenes: vec![BundleScene {
+                id: scene_id,
+                parent_id: None,
+                name: "root".to_string(),
+                metadata: json!({ "version": 1, "description": "", "system_prompt": null }),
+            }],
+            messages: vec![BundleMessage {
+                scene_id,
+                group_id: Uuid::new_v4(),
+                msg_type: MsgType::Text,
+                role: MessageRole::User,
+                source: None,
+                reply_to_group_id: None,
+                content: json!({ "Text": { "body": "hello" } }),
+                metadata: json!({ "device": null, "tags": [null, 1] }),
+                status: MessageStatus::Handled,
+                handled_by: Some("responder".to_string()),
+                created_at: "2026-10-18T00:00:00+00:00".to_string(),
+            }],
+            html_pages: vec![],
+            device_mappings: vec![],
+        }
+    }
+
+    fn assert_round_trip(format: BundleFormat) {
+        let bundle = bundle();
+        let decoded = SceneBundle::decode(&bundle.encode(format).unwrap(), format).unwrap();
+        assert_eq!(decoded.scenes[0].metadata, bundle.scenes[0].metadata);
+        assert_eq!(decoded.messages[0].content, bundle.messages[0].content);
+        assert_eq!(decoded.messages[0].metadata, bundle.messages[0].metadata);
+        assert_eq!(decoded.messages[0].status, MessageStatus::Handled);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        assert_round_trip(BundleFormat::Json);
+    }
+
+    #[test]
+    fn toml_round_trip_keeps_null() {
+        assert_round_trip(BundleFormat::Toml);
+    }
+
+    #[test]
+    fn decode_rejects_other_version() {
+        let mut bundle = bundle();
+        bundle.version = SCENE_BUNDLE_VERSION + 1;
+        let content = bundle.encode(BundleFormat::Json).unwrap();
+        assert!(SceneBundle::decode(&content, BundleFormat::Json).is_err());
+    }
+
+    #[test]
+    fn html_page_path_strips_query_and_fragment() {
+        assert_eq!(
+            html_page_path("http://host/html/a/b.html?x=1#top"),
+            Some("a/b.html")
+        );
+        assert_eq!(html_page_path("http://host/html/page#top"), Some("page"));
+        assert_eq!(html_page_path("http://host/html/?x=1"), None);
+        assert_eq!(html_page_path("http://host/html/"), None);
+        assert_eq!(html_page_path("http://host/page"), None);
+    }
+
+    #[test]
+    fn device_mappings_skips_invalid_entries() {
+        let scene_id = Uuid::new_v4();
+        let config = json!({
+            "auto_connect": [
+                { "device_id": "a", "mapping_url": "http://host/html/a", "scene_id": scene_id },
+                { "device_id": "b" },
+            ]
+        });
+        let mappings = device_mappings(&config);
+        assert_eq!(mappings.len(), 1);
+        assert_eq!(mappings[0].device_id, "a");
+    }
+}
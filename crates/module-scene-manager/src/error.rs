@@ -13,4 +13,19 @@ pub enum SceneError {
 
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+
+    #[error("Invalid scene metadata: {0}")]
+    InvalidMetadata(String),
+
+    #[error(transparent)]
+    TomlSerialize(#[from] toml::ser::Error),
+
+    #[error(transparent)]
+    TomlDeserialize(#[from] toml::de::Error),
+
+    #[error("Invalid scene bundle: {0}")]
+    InvalidBundle(String),
+
+    #[error("Import conflict: {0}")]
+    Conflict(String),
 }
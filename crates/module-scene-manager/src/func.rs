@@ -1,7 +1,12 @@
 use crate::func::create_scene::CreateSceneParam;
 use crate::func::delete_scene::DeleteSceneParam;
+use crate::func::export_scene_bundle::ExportSceneBundleParam;
 use crate::func::get_scene::GetSceneParam;
+use crate::func::get_scene_ancestors::GetSceneAncestorsParam;
+use crate::func::import_scene_bundle::ImportSceneBundleParam;
+use crate::func::list_scene_subtree::ListSceneSubtreeParam;
 use crate::func::list_scenes::ListScenesParam;
+use crate::func::move_scene::MoveSceneParam;
 use crate::func::update_scene::UpdateSceneParam;
 use crate::SceneManager;
 use nihility_module::{BoxStream, Callable, FunctionMetadata, Module};
@@ -11,8 +16,13 @@ use tracing::debug;
 
 pub mod create_scene;
 pub mod delete_scene;
+pub mod export_scene_bundle;
 pub mod get_scene;
+pub mod get_scene_ancestors;
+pub mod import_scene_bundle;
+pub mod list_scene_subtree;
 pub mod list_scenes;
+pub mod move_scene;
 pub mod update_scene;
 
 #[async_trait::async_trait]
@@ -23,9 +33,21 @@ impl Callable for SceneManager {
             "get_scene" => Ok(serde_json::to_value(
                 self.get_scene(serde_json::from_value(param)?).await?,
             )?),
+            "get_scene_ancestors" => Ok(serde_json::to_value(
+                self.get_scene_ancestors(serde_json::from_value(param)?)
+                    .await?,
+            )?),
             "list_scenes" => Ok(serde_json::to_value(
                 self.list_scenes(serde_json::from_value(param)?).await?,
             )?),
+            "list_scene_subtree" => Ok(serde_json::to_value(
+                self.list_scene_subtree(serde_json::from_value(param)?)
+                    .await?,
+            )?),
+            "export_scene_bundle" => Ok(serde_json::to_value(
+                self.export_scene_bundle(serde_json::from_value(param)?)
+                    .await?,
+            )?),
             _ => Err(anyhow::anyhow!("Unsupported func_name: {}", func_name)),
         }
     }
@@ -39,9 +61,16 @@ impl Callable for SceneManager {
             "update_scene" => Ok(serde_json::to_value(
                 self.update_scene(serde_json::from_value(param)?).await?,
             )?),
+            "move_scene" => Ok(serde_json::to_value(
+                self.move_scene(serde_json::from_value(param)?).await?,
+            )?),
             "delete_scene" => Ok(serde_json::to_value(
                 self.delete_scene(serde_json::from_value(param)?).await?,
             )?),
+            "import_scene_bundle" => Ok(serde_json::to_value(
+                self.import_scene_bundle(serde_json::from_value(param)?)
+                    .await?,
+            )?),
             _ => Err(anyhow::anyhow!("Unsupported func_name: {}", func_name)),
         }
     }
@@ -58,7 +87,7 @@ impl Callable for SceneManager {
 
 impl Module for SceneManager {
     fn description(&self) -> &str {
-        "场景管理模块，提供创建、查询、更新、删除场景等功能"
+        "场景管理模块，提供创建、查询、更新、删除、导入导出场景等功能"
     }
 
     fn no_perm_func(&self) -> Vec<FunctionMetadata> {
@@ -70,6 +99,13 @@ impl Module for SceneManager {
                 params: serde_json::to_value(schema_for!(GetSceneParam))
                     .expect("scene func get_scene build param"),
             },
+            FunctionMetadata {
+                name: "get_scene_ancestors".to_string(),
+                desc: "获取场景到根场景的路径".to_string(),
+                tags: vec!["read".into()],
+                params: serde_json::to_value(schema_for!(GetSceneAncestorsParam))
+                    .expect("scene func get_scene_ancestors build param"),
+            },
             FunctionMetadata {
                 name: "list_scenes".to_string(),
                 desc: "列出所有场景，支持按父场景筛选".to_string(),
@@ -77,6 +113,13 @@ impl Module for SceneManager {
                 params: serde_json::to_value(schema_for!(ListScenesParam))
                     .expect("scene func list_scenes build param"),
             },
+            FunctionMetadata {
+                name: "list_scene_subtree".to_string(),
+                desc: "递归列出场景及其全部后代场景，包含层级深度".to_string(),
+                tags: vec!["read".into()],
+                params: serde_json::to_value(schema_for!(ListSceneSubtreeParam))
+                    .expect("scene func list_scene_subtree build param"),
+            },
         ]
     }
 
@@ -96,13 +139,34 @@ impl Module for SceneManager {
                 params: serde_json::to_value(schema_for!(UpdateSceneParam))
                     .expect("scene func update_scene build param"),
             },
+            FunctionMetadata {
+                name: "move_scene".to_string(),
+                desc: "移动场景到新的父场景下，父场景为空时移动为根场景".to_string(),
+                tags: vec!["write".into()],
+                params: serde_json::to_value(schema_for!(MoveSceneParam))
+                    .expect("scene func move_scene build param"),
+            },
             FunctionMetadata {
                 name: "delete_scene".to_string(),
-                desc: "删除场景".to_string(),
+                desc: "删除场景，默认级联删除，可选子场景与消息上移或非空时拒绝".to_string(),
                 tags: vec!["write".into()],
                 params: serde_json::to_value(schema_for!(DeleteSceneParam))
                     .expect("scene func delete_scene build param"),
             },
+            FunctionMetadata {
+                name: "export_scene_bundle".to_string(),
+                desc: "导出场景子树为 JSON 或 TOML 场景包，可包含消息与设备映射".to_string(),
+                tags: vec!["read".into()],
+                params: serde_json::to_value(schema_for!(ExportSceneBundleParam))
+                    .expect("scene func export_scene_bundle build param"),
+            },
+            FunctionMetadata {
+                name: "import_scene_bundle".to_string(),
+                desc: "导入场景包，重新生成ID并按策略处理名称冲突".to_string(),
+                tags: vec!["write".into()],
+                params: serde_json::to_value(schema_for!(ImportSceneBundleParam))
+                    .expect("scene func import_scene_bundle build param"),
+            },
         ]
     }
 }
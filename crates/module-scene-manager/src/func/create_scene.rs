@@ -13,8 +13,9 @@ pub struct CreateSceneParam {
     pub name: String,
     /// 父场景ID（可选，用于层级结构）
     pub parent_id: Option<Uuid>,
-    /// 场景元数据
-    pub metadata: SceneMetadata,
+    /// 场景元数据，按场景元数据 JSON Schema 校验
+    #[schemars(with = "SceneMetadata")]
+    pub metadata: Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,11 +28,12 @@ pub struct CreateSceneResult {
 
 impl SceneManager {
     pub async fn create_scene(&self, param: CreateSceneParam) -> Result<CreateSceneResult> {
+        let metadata = SceneMetadata::from_value(&param.metadata)?;
         let model = nihility_store_operate::scene::insert_scene(
             &self.db,
             param.name,
             param.parent_id,
-            serde_json::to_value(&param.metadata)?,
+            serde_json::to_value(&metadata)?,
         )
         .await?;
 
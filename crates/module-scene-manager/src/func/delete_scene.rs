@@ -1,29 +1,64 @@
 use crate::error::*;
 use crate::SceneManager;
 use nihility_store_operate;
+use nihility_store_operate::scene::SceneDeleteMode;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// 删除场景时子场景的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteMode {
+    /// 删除场景及其全部后代场景与消息
+    #[default]
+    Cascade,
+    /// 子场景与消息移动到被删除场景的父场景下，根场景存在消息时拒绝删除
+    Reparent,
+    /// 场景存在子场景或消息时拒绝删除
+    Restrict,
+}
+
+impl From<DeleteMode> for SceneDeleteMode {
+    fn from(mode: DeleteMode) -> Self {
+        match mode {
+            DeleteMode::Cascade => SceneDeleteMode::Cascade,
+            DeleteMode::Reparent => SceneDeleteMode::Reparent,
+            DeleteMode::Restrict => SceneDeleteMode::Restrict,
+        }
+    }
+}
+
 /// 删除场景参数
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DeleteSceneParam {
     /// 场景ID
     pub id: Uuid,
+    /// 删除方式，默认级联删除
+    #[serde(default)]
+    pub mode: DeleteMode,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteSceneResult {
     pub success: bool,
     pub id: Uuid,
+    /// 被删除的场景ID，包含级联删除的后代场景
+    pub deleted_ids: Vec<Uuid>,
+    /// 移动到父场景的消息数量
+    pub moved_messages: u64,
 }
 
 impl SceneManager {
     pub async fn delete_scene(&self, param: DeleteSceneParam) -> Result<DeleteSceneResult> {
-        nihility_store_operate::scene::delete_scene(&self.db, param.id).await?;
+        let deletion =
+            nihility_store_operate::scene::delete_scene(&self.db, param.id, param.mode.into())
+                .await?;
         Ok(DeleteSceneResult {
             success: true,
             id: param.id,
+            deleted_ids: deletion.deleted_ids,
+            moved_messages: deletion.moved_messages,
         })
     }
 }
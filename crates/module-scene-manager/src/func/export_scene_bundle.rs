@@ -0,0 +1,143 @@
+use crate::SceneManager;
+use crate::bundle::{
+    self, BundleFormat, BundleHtmlPage, BundleMessage, BundleScene, SCENE_BUNDLE_VERSION,
+    SceneBundle,
+};
+use crate::error::*;
+use chrono::Utc;
+use nihility_store_operate::{StoreError, html_page, message, scene};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// 导出场景包参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExportSceneBundleParam {
+    /// 子树根场景ID
+    pub id: Uuid,
+    /// 导出格式（默认 json）
+    #[serde(default)]
+    pub format: BundleFormat,
+    /// 是否包含场景消息（默认 false）
+    #[serde(default)]
+    pub include_messages: bool,
+    /// 是否包含映射到子树场景的设备及其引用的网页（默认 true）
+    #[serde(default = "default_include_device_mappings")]
+    pub include_device_mappings: bool,
+}
+
+fn default_include_device_mappings() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSceneBundleResult {
+    pub format: BundleFormat,
+    /// 序列化后的场景包
+    pub content: String,
+    pub scenes: usize,
+    pub messages: usize,
+    pub html_pages: usize,
+    pub device_mappings: usize,
+}
+
+impl SceneManager {
+    pub async fn export_scene_bundle(
+        &self,
+        param: ExportSceneBundleParam,
+    ) -> Result<ExportSceneBundleResult> {
+        let bundle = self.build_scene_bundle(&param).await?;
+        Ok(ExportSceneBundleResult {
+            format: param.format,
+            content: bundle.encode(param.format)?,
+            scenes: bundle.scenes.len(),
+            messages: bundle.messages.len(),
+            html_pages: bundle.html_pages.len(),
+            device_mappings: bundle.device_mappings.len(),
+        })
+    }
+
+    async fn build_scene_bundle(&self, param: &ExportSceneBundleParam) -> Result<SceneBundle> {
+        let nodes = scene::find_scene_subtree(&self.db, param.id, None).await?;
+        if nodes.is_empty() {
+            return Err(StoreError::NotFound(format!("scene not found: {}", param.id)).into());
+        }
+        let scene_ids = nodes.iter().map(|node| node.scene.id).collect::<Vec<_>>();
+        let scenes = nodes
+            .into_iter()
+            .enumerate()
+            .map(|(index, node)| BundleScene {
+                id: node.scene.id,
+                // 子树根场景导入时挂载到指定位置，不保留原父场景
+                parent_id: node.scene.parent_id.filter(|_| index > 0),
+                name: node.scene.name,
+                metadata: node.scene.metadata,
+            })
+            .collect();
+
+        let messages = if param.include_messages {
+            message::find_messages_by_scene_ids(&self.db, &scene_ids)
+                .await?
+                .into_iter()
+                .map(|msg| BundleMessage {
+                    scene_id: msg.scene_id,
+                    group_id: msg.group_id,
+                    msg_type: msg.msg_type,
+                    role: msg.role,
+                    source: msg.source,
+                    reply_to_group_id: msg.reply_to_group_id,
+                    content: msg.content,
+                    metadata: msg.metadata,
+                    status: msg.status,
+                    handled_by: msg.handled_by,
+                    created_at: msg.created_at.to_rfc3339(),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut device_mappings = Vec::new();
+        let mut html_pages = Vec::new();
+        if param.include_device_mappings
+            && let Some((_, config)) = bundle::find_device_config(&self.db).await?
+        {
+            device_mappings = bundle::device_mappings(&config)
+                .into_iter()
+                .filter(|mapping| scene_ids.contains(&mapping.scene_id))
+                .collect::<Vec<_>>();
+            let mut paths = HashSet::new();
+            for mapping in &device_mappings {
+                let Some(path) = bundle::html_page_path(&mapping.mapping_url) else {
+                    continue;
+                };
+                if !paths.insert(path.to_string()) {
+                    continue;
+                }
+                match html_page::find_by_path(&self.db, path).await {
+                    Ok(page) => html_pages.push(BundleHtmlPage {
+                        path: page.path,
+                        html: page.html,
+                    }),
+                    Err(StoreError::NotFound(_)) => {
+                        tracing::warn!(
+                            "html page referenced by device mapping not found: {}",
+                            path
+                        );
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        Ok(SceneBundle {
+            version: SCENE_BUNDLE_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            scenes,
+            messages,
+            html_pages,
+            device_mappings,
+        })
+    }
+}
@@ -28,7 +28,7 @@ impl SceneManager {
             id: model.id,
             name: model.name,
             parent_id: model.parent_id,
-            metadata: serde_json::from_value(model.metadata)?,
+            metadata: SceneMetadata::from_value(&model.metadata)?,
         })
     }
 }
@@ -0,0 +1,41 @@
+use crate::error::*;
+use crate::func::get_scene::SceneResponse;
+use crate::{SceneManager, SceneMetadata};
+use nihility_store_operate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 获取场景祖先路径参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetSceneAncestorsParam {
+    /// 场景ID
+    pub id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneAncestorsResult {
+    /// 从场景自身到根场景的路径
+    pub path: Vec<SceneResponse>,
+}
+
+impl SceneManager {
+    pub async fn get_scene_ancestors(
+        &self,
+        param: GetSceneAncestorsParam,
+    ) -> Result<SceneAncestorsResult> {
+        let path = nihility_store_operate::scene::find_scene_ancestors(&self.db, param.id)
+            .await?
+            .into_iter()
+            .map(|model| {
+                Ok(SceneResponse {
+                    id: model.id,
+                    name: model.name,
+                    parent_id: model.parent_id,
+                    metadata: SceneMetadata::from_value(&model.metadata)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(SceneAncestorsResult { path })
+    }
+}
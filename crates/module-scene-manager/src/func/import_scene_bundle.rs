@@ -0,0 +1,542 @@
+use crate::bundle::{
+    self, BundleFormat, BundleHtmlPage, BundleScene, ConflictStrategy, SceneBundle,
+};
+use crate::error::*;
+use crate::{ReplyTarget, SceneManager, SceneMetadata};
+use chrono::DateTime;
+use nihility_store_operate::message::{MessageStatus, NewMessage};
+use nihility_store_operate::{StoreError, html_page, message, module_config, scene};
+use schemars::JsonSchema;
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// 导入场景包参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ImportSceneBundleParam {
+    /// 序列化后的场景包
+    pub content: String,
+    /// 场景包格式（默认 json）
+    #[serde(default)]
+    pub format: BundleFormat,
+    /// 子树根场景挂载的父场景ID（可选，为空则导入为根场景）
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    /// 场景名称或网页路径已存在时的处理方式（默认 rename）
+    #[serde(default)]
+    pub on_conflict: ConflictStrategy,
+    /// 是否导入场景包中的消息（默认 true）
+    #[serde(default = "default_include_messages")]
+    pub include_messages: bool,
+    /// 是否导入设备映射及其引用的网页，已存在的设备ID会被跳过（默认 false）
+    #[serde(default)]
+    pub include_device_mappings: bool,
+}
+
+fn default_include_messages() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedScene {
+    /// 场景包中的场景ID
+    pub source_id: Uuid,
+    pub id: Uuid,
+    pub name: String,
+    /// 是否复用了已存在的同名场景
+    pub reused: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedHtmlPage {
+    /// 场景包中的网页路径
+    pub source_path: String,
+    pub path: String,
+    /// 是否复用了已存在的网页
+    pub reused: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSceneBundleResult {
+    /// 子树根场景ID
+    pub root_id: Uuid,
+    pub scenes: Vec<ImportedScene>,
+    pub messages: usize,
+    pub html_pages: Vec<ImportedHtmlPage>,
+    /// 写入的设备映射数量，边缘设备控制模块重启后生效
+    pub device_mappings: usize,
+    /// 因设备ID已存在或模块配置不在数据库中而跳过的设备
+    pub skipped_devices: Vec<String>,
+}
+
+impl SceneManager {
+    /// 导入场景包，重新生成全部ID并处理名称冲突
+    ///
+    /// 先完成校验与冲突处理，再在同一事务中写入，失败时不会留下部分导入的数据
+    pub async fn import_scene_bundle(
+        &self,
+        param: ImportSceneBundleParam,
+    ) -> Result<ImportSceneBundleResult> {
+        let bundle = SceneBundle::decode(&param.content, param.format)?;
+        let order = import_order(&bundle.scenes)?;
+        if let Some(parent_id) = param.parent_id {
+            scene::find_scene_by_id(&self.db, parent_id).await?;
+        }
+
+        // 场景：校验元数据并确定名称
+        let bundle_ids = bundle.scenes.iter().map(|s| s.id).collect::<HashSet<_>>();
+        let mut taken = HashSet::new();
+        let mut conflicts = Vec::new();
+        let mut plans = Vec::with_capacity(order.len());
+        for bundle_scene in order {
+            let mut metadata = SceneMetadata::from_value(&bundle_scene.metadata).map_err(|e| {
+                SceneError::InvalidBundle(format!("scene {}: {}", bundle_scene.name, e))
+            })?;
+            let reply_target = bundle_reply_target(&mut metadata, &bundle_ids);
+            let existing = scene::find_scene_by_name(&self.db, &bundle_scene.name).await?;
+            let (name, reuse) = match (existing, param.on_conflict) {
+                (None, _) => (bundle_scene.name.clone(), None),
+                (Some(existing), ConflictStrategy::Reuse) => (existing.name, Some(existing.id)),
+                (Some(_), ConflictStrategy::Fail) => {
+                    conflicts.push(format!("scene {}", bundle_scene.name));
+                    continue;
+                }
+                (Some(_), ConflictStrategy::Rename) => (
+                    self.free_scene_name(&bundle_scene.name, &taken).await?,
+                    None,
+                ),
+            };
+            taken.insert(name.clone());
+            plans.push((bundle_scene, name, reuse, metadata, reply_target));
+        }
+
+        // 消息：确认所属场景在包内并解析创建时间，复用的场景保留原有消息
+        let messages = if param.include_messages {
+            let reused = plans
+                .iter()
+                .filter(|(_, _, reuse, _, _)| reuse.is_some())
+                .map(|(bundle_scene, _, _, _, _)| bundle_scene.id)
+                .collect::<HashSet<_>>();
+            let mut messages = Vec::with_capacity(bundle.messages.len());
+            for msg in &bundle.messages {
+                if !bundle_ids.contains(&msg.scene_id) {
+                    return Err(SceneError::InvalidBundle(format!(
+                        "message references unknown scene {}",
+                        msg.scene_id
+                    )));
+                }
+                let created_at = DateTime::parse_from_rfc3339(&msg.created_at)
+                    .map_err(|e| SceneError::InvalidBundle(format!("created_at: {}", e)))?;
+                if !reused.contains(&msg.scene_id) {
+                    messages.push((msg, created_at));
+                }
+            }
+            messages
+        } else {
+            Vec::new()
+        };
+
+        // 网页与设备映射
+        let mut pages = Vec::new();
+        let mut page_paths = HashMap::new();
+        let mut device_config = None;
+        let mut mappings = Vec::new();
+        let mut skipped_devices = Vec::new();
+        if param.include_device_mappings {
+            let mut taken_paths = HashSet::new();
+            for BundleHtmlPage { path, html } in &bundle.html_pages {
+                let (target, reuse) = match html_page::find_by_path(&self.db, path).await {
+                    Ok(existing) if existing.html == *html => (existing.path, true),
+                    Ok(existing) => match param.on_conflict {
+                        ConflictStrategy::Reuse => (existing.path, true),
+                        ConflictStrategy::Fail => {
+                            conflicts.push(format!("html page {}", path));
+                            continue;
+                        }
+                        ConflictStrategy::Rename => {
+                            (self.free_page_path(path, &taken_paths).await?, false)
+                        }
+                    },
+                    Err(StoreError::NotFound(_)) => (path.clone(), false),
+                    Err(e) => return Err(e.into()),
+                };
+                taken_paths.insert(target.clone());
+                page_paths.insert(path.clone(), target.clone());
+                pages.push((path, html, target, reuse));
+            }
+
+            match bundle::find_device_config(&self.db).await? {
+                Some((config_id, config)) => {
+                    let existing = bundle::device_mappings(&config)
+                        .into_iter()
+                        .map(|mapping| mapping.device_id)
+                        .collect::<HashSet<_>>();
+                    for mapping in &bundle.device_mappings {
+                        if existing.contains(&mapping.device_id) {
+                            skipped_devices.push(mapping.device_id.clone());
+                        } else {
+                            mappings.push(mapping.clone());
+                        }
+                    }
+                    device_config = Some((config_id, config));
+                }
+                None => skipped_devices.extend(
+                    bundle
+                        .device_mappings
+                        .iter()
+                        .map(|mapping| mapping.device_id.clone()),
+                ),
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(SceneError::Conflict(format!(
+                "already exists: {}",
+                conflicts.join(", ")
+            )));
+        }
+
+        let txn = self.db.begin().await.map_err(StoreError::from)?;
+
+        let mut scene_ids = HashMap::new();
+        let mut scenes = Vec::with_capacity(plans.len());
+        let mut retargets = Vec::new();
+        for (bundle_scene, name, reuse, metadata, reply_target) in plans {
+            let id = match reuse {
+                Some(id) => id,
+                None => {
+                    let parent_id = match bundle_scene.parent_id {
+                        Some(parent_id) => scene_ids.get(&parent_id).copied(),
+                        None => param.parent_id,
+                    };
+                    let id = scene::insert_scene(
+                        &txn,
+                        name.clone(),
+                        parent_id,
+                        serde_json::to_value(&metadata)?,
+                    )
+                    .await?
+                    .id;
+                    if let Some(target) = reply_target {
+                        retargets.push((id, target, metadata));
+                    }
+                    id
+                }
+            };
+            scene_ids.insert(bundle_scene.id, id);
+            scenes.push(ImportedScene {
+                source_id: bundle_scene.id,
+                id,
+                name,
+                reused: reuse.is_some(),
+            });
+        }
+
+        // 回复目标指向包内场景时，待全部场景写入后改为新的场景ID
+        for (id, target, mut metadata) in retargets {
+            metadata.reply_target = ReplyTarget::Scene {
+                scene_id: scene_ids[&target],
+            };
+            scene::update_scene(&txn, id, None, None, Some(serde_json::to_value(metadata)?))
+                .await?;
+        }
+
+        // 包内消息组重新生成ID，回复关系仅保留包内的消息组
+        let bundle_groups = messages
+            .iter()
+            .map(|(msg, _)| msg.group_id)
+            .collect::<HashSet<_>>();
+        let mut group_ids = HashMap::new();
+        for (msg, created_at) in &messages {
+            let group_id = *group_ids.entry(msg.group_id).or_insert_with(Uuid::new_v4);
+            let reply_to_group_id = msg
+                .reply_to_group_id
+                .filter(|group_id| bundle_groups.contains(group_id))
+                .map(|group_id| *group_ids.entry(group_id).or_insert_with(Uuid::new_v4));
+            // 导入的消息不再进入分析链
+            let status = match msg.status {
+                MessageStatus::Pending | MessageStatus::Analyzing => MessageStatus::Ignored,
+                ref status => status.clone(),
+            };
+            message::insert_imported_message(
+                &txn,
+                NewMessage {
+                    scene_id: scene_ids[&msg.scene_id],
+                    msg_type: msg.msg_type.clone(),
+                    role: msg.role.clone(),
+                    source: msg.source.clone(),
+                    reply_to_group_id,
+                    content: msg.content.clone(),
+                    metadata: msg.metadata.clone(),
+                    group_id,
+                    is_processed: true,
+                },
+                status,
+                msg.handled_by.clone(),
+                *created_at,
+            )
+            .await?;
+        }
+
+        let mut html_pages = Vec::with_capacity(pages.len());
+        for (source_path, html, path, reuse) in pages {
+            if !reuse {
+                html_page::create(&txn, path.clone(), html.clone()).await?;
+            }
+            html_pages.push(ImportedHtmlPage {
+                source_path: source_path.clone(),
+                path,
+                reused: reuse,
+            });
+        }
+
+        let device_mappings = mappings.len();
+        if let Some((config_id, mut config)) = device_config
+            && !mappings.is_empty()
+        {
+            let mut auto_connect = config
+                .get("auto_connect")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            for mut mapping in mappings {
+                mapping.scene_id = scene_ids[&mapping.scene_id];
+                if let Some(path) = bundle::html_page_path(&mapping.mapping_url)
+                    && let Some(target) = page_paths.get(path)
+                    && target != path
+                {
+                    mapping.mapping_url = mapping.mapping_url.replacen(
+                        &format!("/html/{}", path),
+                        &format!("/html/{}", target),
+                        1,
+                    );
+                }
+                auto_connect.push(serde_json::to_value(mapping)?);
+            }
+            match config.as_object_mut() {
+                Some(object) => {
+                    object.insert("auto_connect".to_string(), Value::Array(auto_connect));
+                }
+                None => config = serde_json::json!({ "auto_connect": auto_connect }),
+            }
+            module_config::update_config_value(&txn, &config_id, config).await?;
+        }
+
+        txn.commit().await.map_err(StoreError::from)?;
+
+        Ok(ImportSceneBundleResult {
+            root_id: scenes[0].id,
+            scenes,
+            messages: messages.len(),
+            html_pages,
+            device_mappings,
+            skipped_devices,
+        })
+    }
+
+    /// 生成未被占用的场景名称，依次尝试 `name-2`、`name-3`……
+    async fn free_scene_name(&self, name: &str, taken: &HashSet<String>) -> Result<String> {
+        for n in 2.. {
+            let candidate = format!("{}-{}", name, n);
+            if !taken.contains(&candidate)
+                && scene::find_scene_by_name(&self.db, &candidate)
+                    .await?
+                    .is_none()
+            {
+                return Ok(candidate);
+            }
+        }
+        unreachable!()
+    }
+
+    /// 生成未被占用的网页路径，后缀追加在扩展名之前，例如 `foo-2.html`
+    async fn free_page_path(&self, path: &str, taken: &HashSet<String>) -> Result<String> {
+        for n in 2.. {
+            let candidate = suffixed_path(path, n);
+            if taken.contains(&candidate) {
+                continue;
+            }
+            match html_page::find_by_path(&self.db, &candidate).await {
+                Err(StoreError::NotFound(_)) => return Ok(candidate),
+                Err(e) => return Err(e.into()),
+                Ok(_) => {}
+            }
+        }
+        unreachable!()
+    }
+}
+
+/// 返回指向包内场景的回复目标，指向包外场景时重置为消息来源场景
+///
+/// 包外的场景ID来自导出方的数据库，在导入方不存在或指向无关的场景
+fn bundle_reply_target(metadata: &mut SceneMetadata, bundle_ids: &HashSet<Uuid>) -> Option<Uuid> {
+    match metadata.reply_target {
+        ReplyTarget::Scene { scene_id } if bundle_ids.contains(&scene_id) => Some(scene_id),
+        ReplyTarget::Scene { .. } => {
+            metadata.reply_target = ReplyTarget::Source;
+            None
+        }
+        ReplyTarget::Source | ReplyTarget::Parent => None,
+    }
+}
+
+/// 在路径最后一段的扩展名前追加数字后缀
+fn suffixed_path(path: &str, n: u32) -> String {
+    let name_start = path.rfind('/').map_or(0, |index| index + 1);
+    match path[name_start..].rfind('.') {
+        Some(dot) if dot > 0 => {
+            let (stem, extension) = path.split_at(name_start + dot);
+            format!("{}-{}{}", stem, n, extension)
+        }
+        _ => format!("{}-{}", path, n),
+    }
+}
+
+/// 校验场景包中的场景构成一棵树，返回父场景在前的导入顺序
+fn import_order(scenes: &[BundleScene]) -> Result<Vec<&BundleScene>> {
+    let mut ids = HashSet::new();
+    let mut names = HashSet::new();
+    for scene in scenes {
+        if !ids.insert(scene.id) || !names.insert(scene.name.as_str()) {
+            return Err(SceneError::InvalidBundle(format!(
+                "duplicate scene: {}",
+                scene.name
+            )));
+        }
+    }
+    let mut roots = scenes.iter().filter(|scene| scene.parent_id.is_none());
+    let (Some(root), None) = (roots.next(), roots.next()) else {
+        return Err(SceneError::InvalidBundle(
+            "bundle must contain exactly one root scene".to_string(),
+        ));
+    };
+
+    let mut order = vec![root];
+    let mut index = 0;
+    while index < order.len() {
+        let parent_id = order[index].id;
+        order.extend(
+            scenes
+                .iter()
+                .filter(|scene| scene.parent_id == Some(parent_id)),
+        );
+        index += 1;
+    }
+    if order.len() != scenes.len() {
+        return Err(SceneError::InvalidBundle(
+            "scenes are not connected to the root scene".to_string(),
+        ));
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scene(name: &str, parent_id: Option<Uuid>) -> BundleScene {
+        BundleScene {
+            id: Uuid::new_v4(),
+            parent_id,
+            name: name.to_string(),
+            metadata: json!({ "version": 1, "description": "" }),
+        }
+    }
+
+    fn names(order: &[&BundleScene]) -> Vec<String> {
+        order.iter().map(|scene| scene.name.clone()).collect()
+    }
+
+    #[test]
+    fn import_order_puts_parents_first() {
+        let root = scene("root", None);
+        let child = scene("child", Some(root.id));
+        let grandchild = scene("grandchild", Some(child.id));
+        let scenes = vec![grandchild, child, root];
+        let order = import_order(&scenes).unwrap();
+        assert_eq!(names(&order), ["root", "child", "grandchild"]);
+    }
+
+    #[test]
+    fn import_order_rejects_duplicate_names() {
+        let root = scene("root", None);
+        let scenes = vec![scene("a", Some(root.id)), scene("a", Some(root.id)), root];
+        assert!(import_order(&scenes).is_err());
+    }
+
+    #[test]
+    fn import_order_rejects_duplicate_ids() {
+        let root = scene("root", None);
+        let mut child = scene("child", Some(root.id));
+        child.id = root.id;
+        assert!(import_order(&[root, child]).is_err());
+    }
+
+    #[test]
+    fn import_order_requires_exactly_one_root() {
+        assert!(import_order(&[]).is_err());
+        assert!(import_order(&[scene("a", None), scene("b", None)]).is_err());
+        let a = scene("a", Some(Uuid::new_v4()));
+        let b = scene("b", Some(a.id));
+        assert!(import_order(&[a, b]).is_err());
+    }
+
+    #[test]
+    fn import_order_rejects_disconnected_scenes() {
+        let root = scene("root", None);
+        let mut a = scene("a", None);
+        let b = scene("b", Some(a.id));
+        a.parent_id = Some(b.id);
+        assert!(import_order(&[root, a, b]).is_err());
+    }
+
+    #[test]
+    fn bundle_reply_target_keeps_scenes_inside_bundle() {
+        let target = Uuid::new_v4();
+        let mut metadata = SceneMetadata {
+            reply_target: ReplyTarget::Scene { scene_id: target },
+            ..Default::default()
+        };
+        let bundle_ids = HashSet::from([Uuid::new_v4(), target]);
+        assert_eq!(
+            bundle_reply_target(&mut metadata, &bundle_ids),
+            Some(target)
+        );
+        assert_eq!(
+            metadata.reply_target,
+            ReplyTarget::Scene { scene_id: target }
+        );
+    }
+
+    #[test]
+    fn bundle_reply_target_resets_scenes_outside_bundle() {
+        let mut metadata = SceneMetadata {
+            reply_target: ReplyTarget::Scene {
+                scene_id: Uuid::new_v4(),
+            },
+            ..Default::default()
+        };
+        let bundle_ids = HashSet::from([Uuid::new_v4()]);
+        assert_eq!(bundle_reply_target(&mut metadata, &bundle_ids), None);
+        assert_eq!(metadata.reply_target, ReplyTarget::Source);
+
+        let mut metadata = SceneMetadata {
+            reply_target: ReplyTarget::Parent,
+            ..Default::default()
+        };
+        assert_eq!(bundle_reply_target(&mut metadata, &bundle_ids), None);
+        assert_eq!(metadata.reply_target, ReplyTarget::Parent);
+    }
+
+    #[test]
+    fn suffixed_path_keeps_extension() {
+        assert_eq!(suffixed_path("foo.html", 2), "foo-2.html");
+        assert_eq!(suffixed_path("a.b/page", 3), "a.b/page-3");
+        assert_eq!(suffixed_path("dir/foo.tar.gz", 2), "dir/foo.tar-2.gz");
+        assert_eq!(suffixed_path(".hidden", 2), ".hidden-2");
+        assert_eq!(suffixed_path("page", 2), "page-2");
+    }
+}
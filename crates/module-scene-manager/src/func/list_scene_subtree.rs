@@ -0,0 +1,70 @@
+use crate::error::*;
+use crate::func::list_scenes::InvalidSceneItem;
+use crate::{SceneManager, SceneMetadata};
+use nihility_store_operate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 列出场景子树参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListSceneSubtreeParam {
+    /// 子树根场景ID
+    pub id: Uuid,
+    /// 最大深度（可选，为空则不限制，根场景深度为 0）
+    pub max_depth: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneTreeItem {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    pub metadata: SceneMetadata,
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListSceneSubtreeResult {
+    pub scenes: Vec<SceneTreeItem>,
+    /// 元数据不合法的场景
+    pub invalid_scenes: Vec<InvalidSceneItem>,
+    /// 场景总数，包含元数据不合法的场景
+    pub total: usize,
+}
+
+impl SceneManager {
+    pub async fn list_scene_subtree(
+        &self,
+        param: ListSceneSubtreeParam,
+    ) -> Result<ListSceneSubtreeResult> {
+        // 确认根场景存在
+        nihility_store_operate::scene::find_scene_by_id(&self.db, param.id).await?;
+        let nodes =
+            nihility_store_operate::scene::find_scene_subtree(&self.db, param.id, param.max_depth)
+                .await?;
+
+        let mut items = Vec::new();
+        let mut invalid_scenes = Vec::new();
+        for node in nodes {
+            let s = node.scene;
+            match SceneMetadata::from_value(&s.metadata) {
+                Ok(metadata) => items.push(SceneTreeItem {
+                    id: s.id,
+                    name: s.name,
+                    parent_id: s.parent_id,
+                    metadata,
+                    depth: node.depth,
+                }),
+                Err(e) => invalid_scenes.push(InvalidSceneItem::new(s, e)),
+            }
+        }
+
+        let total = items.len() + invalid_scenes.len();
+        Ok(ListSceneSubtreeResult {
+            scenes: items,
+            invalid_scenes,
+            total,
+        })
+    }
+}
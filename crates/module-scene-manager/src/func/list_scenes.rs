@@ -3,7 +3,8 @@ use crate::{SceneManager, SceneMetadata};
 use nihility_store_operate;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tracing::error;
+use serde_json::Value;
+use tracing::warn;
 use uuid::Uuid;
 
 /// 列出场景参数
@@ -21,9 +22,37 @@ pub struct SceneItem {
     pub metadata: SceneMetadata,
 }
 
+/// 元数据校验失败的场景
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidSceneItem {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    /// 原始元数据
+    pub metadata: Value,
+    /// 校验错误
+    pub error: String,
+}
+
+impl InvalidSceneItem {
+    pub(crate) fn new(scene: nihility_store_operate::scene::SceneModel, error: SceneError) -> Self {
+        warn!("invalid metadata of scene {}: {}", scene.id, error);
+        Self {
+            id: scene.id,
+            name: scene.name,
+            parent_id: scene.parent_id,
+            metadata: scene.metadata,
+            error: error.to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListScenesResult {
     pub scenes: Vec<SceneItem>,
+    /// 元数据不合法的场景
+    pub invalid_scenes: Vec<InvalidSceneItem>,
+    /// 场景总数，包含元数据不合法的场景
     pub total: usize,
 }
 
@@ -35,28 +64,24 @@ impl SceneManager {
             nihility_store_operate::scene::find_all_scenes(&self.db).await?
         };
 
-        let items: Vec<SceneItem> = scenes
-            .into_iter()
-            .filter_map(|s| {
-                let metadata = match serde_json::from_value::<SceneMetadata>(s.metadata.clone()) {
-                    Ok(m) => m,
-                    Err(e) => {
-                        error!("invalid scene metadata: {}", e);
-                        return None;
-                    }
-                };
-                Some(SceneItem {
+        let mut items = Vec::new();
+        let mut invalid_scenes = Vec::new();
+        for s in scenes {
+            match SceneMetadata::from_value(&s.metadata) {
+                Ok(metadata) => items.push(SceneItem {
                     id: s.id,
                     name: s.name,
                     parent_id: s.parent_id,
                     metadata,
-                })
-            })
-            .collect();
+                }),
+                Err(e) => invalid_scenes.push(InvalidSceneItem::new(s, e)),
+            }
+        }
 
-        let total = items.len();
+        let total = items.len() + invalid_scenes.len();
         Ok(ListScenesResult {
             scenes: items,
+            invalid_scenes,
             total,
         })
     }
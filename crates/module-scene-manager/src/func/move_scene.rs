@@ -0,0 +1,30 @@
+use crate::error::*;
+use crate::func::get_scene::SceneResponse;
+use crate::{SceneManager, SceneMetadata};
+use nihility_store_operate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 移动场景参数
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MoveSceneParam {
+    /// 场景ID
+    pub id: Uuid,
+    /// 新的父场景ID，为空时移动为根场景
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+}
+
+impl SceneManager {
+    pub async fn move_scene(&self, param: MoveSceneParam) -> Result<SceneResponse> {
+        let model =
+            nihility_store_operate::scene::move_scene(&self.db, param.id, param.parent_id).await?;
+        Ok(SceneResponse {
+            id: model.id,
+            name: model.name,
+            parent_id: model.parent_id,
+            metadata: SceneMetadata::from_value(&model.metadata)?,
+        })
+    }
+}
@@ -3,6 +3,7 @@ use crate::{SceneManager, SceneMetadata};
 use nihility_store_operate;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
 /// 更新场景参数
@@ -12,10 +13,11 @@ pub struct UpdateSceneParam {
     pub id: Uuid,
     /// 场景名称（可选）
     pub name: Option<String>,
-    /// 父场景ID（可选）
+    /// 父场景ID（可选），不能是场景自身或其后代场景，移动为根场景请使用 move_scene
     pub parent_id: Option<Uuid>,
-    /// 场景元数据（可选）
-    pub metadata: Option<SceneMetadata>,
+    /// 场景元数据（可选），按场景元数据 JSON Schema 校验
+    #[schemars(with = "Option<SceneMetadata>")]
+    pub metadata: Option<Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,7 +31,7 @@ pub struct UpdateSceneResult {
 impl SceneManager {
     pub async fn update_scene(&self, param: UpdateSceneParam) -> Result<UpdateSceneResult> {
         let metadata = if let Some(metadata) = &param.metadata {
-            Some(serde_json::to_value(metadata)?)
+            Some(serde_json::to_value(SceneMetadata::from_value(metadata)?)?)
         } else {
             None
         };
@@ -46,7 +48,7 @@ impl SceneManager {
             id: model.id,
             name: model.name,
             parent_id: model.parent_id,
-            metadata: serde_json::from_value(model.metadata)?,
+            metadata: SceneMetadata::from_value(&model.metadata)?,
         })
     }
 }
@@ -1,5 +1,7 @@
+pub mod bundle;
 pub mod error;
 pub mod func;
+pub mod metadata;
 
 use crate::error::*;
 use schemars::JsonSchema;
@@ -7,6 +9,8 @@ use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+pub use metadata::{ReplyTarget, RetentionPolicy, SCENE_METADATA_VERSION, SceneMetadata};
+
 /// 场景管理模块配置
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SceneManagerConfig {}
@@ -25,12 +29,6 @@ pub struct Scene {
     pub metadata: SceneMetadata,
 }
 
-/// 场景元数据
-#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
-pub struct SceneMetadata {
-    pub description: String,
-}
-
 impl SceneManager {
     pub async fn init_from_file_config(db: DatabaseConnection) -> Result<Self> {
         Self::init(SceneManagerConfig {}, db).await
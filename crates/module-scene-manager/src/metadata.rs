@@ -0,0 +1,126 @@
+use crate::error::*;
+use jsonschema::Validator;
+use schemars::{JsonSchema, schema_for};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+/// 当前场景元数据版本
+pub const SCENE_METADATA_VERSION: u32 = 1;
+
+/// 由场景元数据结构生成的 JSON Schema 校验器
+static VALIDATOR: LazyLock<Validator> = LazyLock::new(|| {
+    let schema =
+        serde_json::to_value(schema_for!(SceneMetadata)).expect("scene metadata build schema");
+    jsonschema::validator_for(&schema).expect("scene metadata compile schema")
+});
+
+fn default_version() -> u32 {
+    SCENE_METADATA_VERSION
+}
+
+/// 场景元数据
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SceneMetadata {
+    /// 元数据版本
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// 场景描述
+    pub description: String,
+    /// 覆盖回复生成时的系统提示词
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// 优先使用的模型名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 启用的分析器名称，为空时启用全部分析器
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analyzers: Option<Vec<String>>,
+    /// 回复发送的目标场景
+    #[serde(default)]
+    pub reply_target: ReplyTarget,
+    /// 消息保留策略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention: Option<RetentionPolicy>,
+    /// 自定义扩展字段
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for SceneMetadata {
+    fn default() -> Self {
+        Self {
+            version: SCENE_METADATA_VERSION,
+            description: String::new(),
+            system_prompt: None,
+            model: None,
+            analyzers: None,
+            reply_target: ReplyTarget::default(),
+            retention: None,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl SceneMetadata {
+    /// 使用生成的 JSON Schema 校验并解析场景元数据，错误信息包含全部不合法的字段路径
+    pub fn from_value(value: &Value) -> Result<Self> {
+        let errors = VALIDATOR
+            .iter_errors(value)
+            .map(|e| {
+                let path = e.instance_path().to_string();
+                format!("{}: {}", if path.is_empty() { "/" } else { &path }, e)
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(SceneError::InvalidMetadata(errors.join("; ")));
+        }
+        let metadata: Self = serde_json::from_value(value.clone())?;
+        if metadata.version != SCENE_METADATA_VERSION {
+            return Err(SceneError::InvalidMetadata(format!(
+                "unsupported version {}, expected {}",
+                metadata.version, SCENE_METADATA_VERSION
+            )));
+        }
+        Ok(metadata)
+    }
+
+    /// 是否启用指定分析器
+    pub fn analyzer_enabled(&self, name: &str) -> bool {
+        self.analyzers
+            .as_ref()
+            .is_none_or(|analyzers| analyzers.iter().any(|analyzer| analyzer == name))
+    }
+}
+
+/// 回复发送的目标场景
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplyTarget {
+    /// 消息来源场景
+    #[default]
+    Source,
+    /// 消息来源场景的父场景，根场景回复到自身
+    Parent,
+    /// 指定场景
+    Scene { scene_id: Uuid },
+}
+
+/// 场景消息保留策略
+///
+/// 仅清理已处理的非摘要消息，多个条件同时设置时满足任意一个即过期
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionPolicy {
+    /// 消息最长保留天数
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+    /// 最多保留的最近消息数量
+    #[serde(default)]
+    pub max_count: Option<u64>,
+    /// 是否只保留摘要，已被摘要覆盖的消息视为过期
+    #[serde(default)]
+    pub keep_only_summaries: bool,
+}
@@ -0,0 +1,22 @@
+pub(crate) type Result<T> = core::result::Result<T, WasmError>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WasmError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Runtime(#[from] wasmtime::Error),
+
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Task(#[from] tokio::task::JoinError),
+
+    #[error("Wasm module file not found: {0}")]
+    ModuleNotFound(String),
+
+    #[error("Wasm module call failed: {0}")]
+    Guest(String),
+}
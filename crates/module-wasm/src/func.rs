@@ -0,0 +1,123 @@
+use crate::error::WasmError;
+use crate::{HostState, WasmModule, bindings};
+use nihility_module::{BoxStream, Callable, FunctionMetadata, Module};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+use wasmtime::Store;
+
+#[async_trait::async_trait]
+impl Callable for WasmModule {
+    async fn call(&self, func_name: &str, param: Value) -> anyhow::Result<Value> {
+        debug!(func_name = %func_name, param = ?param, "Wasm module call");
+        let mut store = self.store.lock().await;
+        let result = self
+            .instance
+            .call_call(&mut *store, func_name, &serde_json::to_string(&param)?)
+            .await?
+            .map_err(WasmError::Guest)?;
+        Ok(serde_json::from_str(&result)?)
+    }
+
+    async fn call_mut(&mut self, func_name: &str, param: Value) -> anyhow::Result<Value> {
+        debug!(func_name = %func_name, param = ?param, "Wasm module call_mut");
+        let mut store = self.store.lock().await;
+        let result = self
+            .instance
+            .call_call_mut(&mut *store, func_name, &serde_json::to_string(&param)?)
+            .await?
+            .map_err(WasmError::Guest)?;
+        Ok(serde_json::from_str(&result)?)
+    }
+
+    async fn call_stream(&self, func_name: &str, param: Value) -> anyhow::Result<BoxStream<Value>> {
+        debug!(func_name = %func_name, param = ?param, "Wasm module call_stream");
+        let handle = {
+            let mut store = self.store.lock().await;
+            self.instance
+                .call_call_stream(&mut *store, func_name, &serde_json::to_string(&param)?)
+                .await?
+                .map_err(WasmError::Guest)?
+        };
+        let chunks = ChunkStream {
+            store: self.store.clone(),
+            instance: self.instance.clone(),
+            handle,
+            finished: false,
+        };
+        // 每次只在读取分片时占用 store，分片按模块产出的顺序逐个返回
+        let stream = futures::stream::unfold(Some(chunks), |state| async move {
+            let mut chunks = state?;
+            match chunks.next_chunk().await {
+                Ok(Some(chunk)) => Some((Ok(chunk), Some(chunks))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
+
+/// 模块中的流式调用，未读取完毕就被释放时通知模块关闭该流
+struct ChunkStream {
+    store: Arc<Mutex<Store<HostState>>>,
+    instance: Arc<bindings::Module>,
+    handle: u32,
+    finished: bool,
+}
+
+impl ChunkStream {
+    async fn next_chunk(&mut self) -> anyhow::Result<Option<Value>> {
+        let mut store = self.store.lock().await;
+        // 返回 none 或错误后模块已释放该流
+        self.finished = true;
+        let chunk = self
+            .instance
+            .call_stream_next(&mut *store, self.handle)
+            .await?
+            .map_err(WasmError::Guest)?;
+        match chunk {
+            Some(chunk) => {
+                self.finished = false;
+                Ok(Some(serde_json::from_str(&chunk)?))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl Drop for ChunkStream {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!(handle = self.handle, "No runtime to close wasm stream");
+            return;
+        };
+        let store = self.store.clone();
+        let instance = self.instance.clone();
+        let handle = self.handle;
+        runtime.spawn(async move {
+            let mut store = store.lock().await;
+            if let Err(e) = instance.call_stream_close(&mut *store, handle).await {
+                warn!(handle = handle, "Failed to close wasm stream: {}", e);
+            }
+        });
+    }
+}
+
+impl Module for WasmModule {
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn no_perm_func(&self) -> Vec<FunctionMetadata> {
+        self.no_perm_func.clone()
+    }
+
+    fn perm_func(&mut self) -> Vec<FunctionMetadata> {
+        self.perm_func.clone()
+    }
+}
@@ -0,0 +1,122 @@
+pub mod error;
+pub mod func;
+
+use crate::bindings::nihility::module::types::LogLevel;
+use crate::error::*;
+use nihility_module::FunctionMetadata;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, trace, warn};
+use wasmtime::component::{Component, HasSelf, Linker};
+use wasmtime::{Config, Engine, Store};
+
+mod bindings {
+    wasmtime::component::bindgen!({
+        path: "wit",
+        world: "module",
+        exports: { default: async },
+    });
+}
+
+/// WASM 模块运行时状态
+struct HostState {
+    /// 模块文件路径，用于日志区分
+    path: String,
+}
+
+/// 通过 WASM 组件加载的子模块
+pub struct WasmModule {
+    store: Arc<Mutex<Store<HostState>>>,
+    instance: Arc<bindings::Module>,
+    description: String,
+    no_perm_func: Vec<FunctionMetadata>,
+    perm_func: Vec<FunctionMetadata>,
+}
+
+impl WasmModule {
+    /// 加载指定路径的 WASM 组件
+    pub async fn init(path: &str) -> Result<Self> {
+        if !Path::new(path).exists() {
+            return Err(WasmError::ModuleNotFound(path.to_string()));
+        }
+
+        let mut config = Config::new();
+        config.async_support(true);
+        config.wasm_component_model(true);
+        let engine = Engine::new(&config)?;
+
+        // 组件编译是同步的耗时操作，放到阻塞线程池执行
+        let component = {
+            let engine = engine.clone();
+            let path = path.to_string();
+            tokio::task::spawn_blocking(move || Component::from_file(&engine, path)).await??
+        };
+        let mut linker = Linker::<HostState>::new(&engine);
+        bindings::Module::add_to_linker::<_, HasSelf<_>>(&mut linker, |state| state)?;
+
+        let mut store = Store::new(
+            &engine,
+            HostState {
+                path: path.to_string(),
+            },
+        );
+        let instance = bindings::Module::instantiate_async(&mut store, &component, &linker).await?;
+
+        let description = instance.call_description(&mut store).await?;
+        let no_perm_func = instance
+            .call_no_perm_func(&mut store)
+            .await?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>>>()?;
+        let perm_func = instance
+            .call_perm_func(&mut store)
+            .await?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>>>()?;
+        info!(
+            "Wasm module {} loaded with {} functions",
+            path,
+            no_perm_func.len() + perm_func.len()
+        );
+
+        Ok(Self {
+            store: Arc::new(Mutex::new(store)),
+            instance: Arc::new(instance),
+            description,
+            no_perm_func,
+            perm_func,
+        })
+    }
+}
+
+impl TryFrom<bindings::nihility::module::types::FunctionMetadata> for FunctionMetadata {
+    type Error = WasmError;
+
+    fn try_from(
+        metadata: bindings::nihility::module::types::FunctionMetadata,
+    ) -> core::result::Result<Self, Self::Error> {
+        Ok(FunctionMetadata {
+            name: metadata.name,
+            desc: metadata.desc,
+            tags: metadata.tags,
+            params: serde_json::from_str(&metadata.params)?,
+        })
+    }
+}
+
+impl bindings::nihility::module::types::Host for HostState {}
+
+impl bindings::ModuleImports for HostState {
+    fn log(&mut self, level: LogLevel, message: String) {
+        match level {
+            LogLevel::Trace => trace!(module = %self.path, "{}", message),
+            LogLevel::Debug => debug!(module = %self.path, "{}", message),
+            LogLevel::Info => info!(module = %self.path, "{}", message),
+            LogLevel::Warn => warn!(module = %self.path, "{}", message),
+            LogLevel::Error => error!(module = %self.path, "{}", message),
+        }
+    }
+}
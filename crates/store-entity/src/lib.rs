@@ -2,8 +2,11 @@
 
 pub mod prelude;
 
+pub mod analysis_job;
+pub mod analysis_result;
 pub mod html_pages;
 pub mod message;
+pub mod message_embedding;
 pub mod module_config;
 pub mod scene;
 pub mod sea_orm_active_enums;
@@ -1,5 +1,7 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0
 
+use super::sea_orm_active_enums::MessageRole;
+use super::sea_orm_active_enums::MessageStatus;
 use super::sea_orm_active_enums::MsgType;
 use sea_orm::entity::prelude::*;
 
@@ -19,6 +21,12 @@ pub struct Model {
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
     pub group_id: Uuid,
+    pub role: MessageRole,
+    pub source: Option<String>,
+    pub reply_to_group_id: Option<Uuid>,
+    pub status: MessageStatus,
+    pub handled_by: Option<String>,
+    pub handled_at: Option<DateTimeWithTimeZone>,
     #[sea_orm(
         belongs_to,
         from = "scene_id",
@@ -1,7 +1,10 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0
 
+pub use super::analysis_job::Entity as AnalysisJob;
+pub use super::analysis_result::Entity as AnalysisResult;
 pub use super::html_pages::Entity as HtmlPages;
 pub use super::message::Entity as Message;
+pub use super::message_embedding::Entity as MessageEmbedding;
 pub use super::module_config::Entity as ModuleConfig;
 pub use super::scene::Entity as Scene;
 pub use super::user::Entity as User;
@@ -3,7 +3,59 @@
 use sea_orm::entity::prelude::*;
 
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "Enum", db_type = "Enum", enum_name = "analysis_job_status")]
+pub enum AnalysisJobStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "running")]
+    Running,
+    #[sea_orm(string_value = "succeeded")]
+    Succeeded,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, serde::Serialize, serde::Deserialize,
+)]
+#[sea_orm(rs_type = "Enum", db_type = "Enum", enum_name = "message_role")]
+#[serde(rename_all = "snake_case")]
+pub enum MessageRole {
+    #[sea_orm(string_value = "user")]
+    User,
+    #[sea_orm(string_value = "assistant")]
+    Assistant,
+    #[sea_orm(string_value = "system")]
+    System,
+    #[sea_orm(string_value = "device")]
+    Device,
+    #[sea_orm(string_value = "tool")]
+    Tool,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, serde::Serialize, serde::Deserialize,
+)]
+#[sea_orm(rs_type = "Enum", db_type = "Enum", enum_name = "message_status")]
+#[serde(rename_all = "snake_case")]
+pub enum MessageStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "analyzing")]
+    Analyzing,
+    #[sea_orm(string_value = "handled")]
+    Handled,
+    #[sea_orm(string_value = "ignored")]
+    Ignored,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, serde::Serialize, serde::Deserialize,
+)]
 #[sea_orm(rs_type = "Enum", db_type = "Enum", enum_name = "msg_type")]
+#[serde(rename_all = "snake_case")]
 pub enum MsgType {
     #[sea_orm(string_value = "text")]
     Text,
@@ -13,4 +65,15 @@ pub enum MsgType {
     Image,
     #[sea_orm(string_value = "video")]
     Video,
+    #[sea_orm(string_value = "summary")]
+    Summary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "Enum", db_type = "Enum", enum_name = "user_role")]
+pub enum UserRole {
+    #[sea_orm(string_value = "admin")]
+    Admin,
+    #[sea_orm(string_value = "user")]
+    User,
 }
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0
 
+use super::sea_orm_active_enums::UserRole;
 use sea_orm::entity::prelude::*;
 
 #[sea_orm::model]
@@ -11,6 +12,7 @@ pub struct Model {
     #[sea_orm(unique)]
     pub name: String,
     pub password: String,
+    pub role: UserRole,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
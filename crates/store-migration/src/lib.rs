@@ -1,5 +1,5 @@
-use sea_orm_migration::prelude::*;
 pub use sea_orm_migration::MigratorTrait;
+use sea_orm_migration::prelude::*;
 
 pub struct Migrator;
 
@@ -13,6 +13,17 @@ impl MigratorTrait for Migrator {
             Box::new(m20260408_000001_message_pool::Migration),
             Box::new(m20260415_112507_add_message_group_id::Migration),
             Box::new(m20260416_123542_base_scene::Migration),
+            Box::new(m20261018_090512_user_role::Migration),
+            Box::new(m20261018_101233_analysis_result::Migration),
+            Box::new(m20261018_113024_message_embedding::Migration),
+            Box::new(m20261018_124816_summary_msg_type::Migration),
+            Box::new(m20261018_135204_message_role::Migration),
+            Box::new(m20261018_142536_message_thread::Migration),
+            Box::new(m20261018_151037_message_created_at_index::Migration),
+            Box::new(m20261018_160412_analysis_job::Migration),
+            Box::new(m20261018_171845_message_status::Migration),
+            Box::new(m20261018_184210_scene_metadata_v1::Migration),
+            Box::new(m20261018_193527_analysis_job_backfill::Migration),
         ]
     }
 }
@@ -22,3 +33,14 @@ mod m20260330_000001_module_config;
 mod m20260408_000001_message_pool;
 mod m20260415_112507_add_message_group_id;
 mod m20260416_123542_base_scene;
+mod m20261018_090512_user_role;
+mod m20261018_101233_analysis_result;
+mod m20261018_113024_message_embedding;
+mod m20261018_124816_summary_msg_type;
+mod m20261018_135204_message_role;
+mod m20261018_142536_message_thread;
+mod m20261018_151037_message_created_at_index;
+mod m20261018_160412_analysis_job;
+mod m20261018_171845_message_status;
+mod m20261018_184210_scene_metadata_v1;
+mod m20261018_193527_analysis_job_backfill;
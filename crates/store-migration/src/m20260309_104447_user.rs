@@ -2,9 +2,7 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
     Argon2,
 };
-use nihility_store_entity::user;
 use nihility_util_secret::generate_secret;
-use sea_orm_migration::sea_orm::{ActiveModelTrait, Set};
 use sea_orm_migration::{prelude::*, schema::*};
 use tracing::info;
 use uuid::Uuid;
@@ -27,8 +25,6 @@ impl MigrationTrait for Migration {
             )
             .await?;
 
-        let conn = manager.get_connection();
-
         let password = generate_secret(16);
         info!("Generated Password is {}", password);
 
@@ -39,13 +35,20 @@ impl MigrationTrait for Migration {
             .expect("build password hash fail")
             .to_string();
 
-        user::ActiveModel {
-            id: Set(Uuid::new_v4()),
-            name: Set("admin".to_string()),
-            password: Set(password_hash),
-        }
-        .insert(conn)
-        .await?;
+        // 用户实体会随后续迁移增加字段，这里按建表时的列写入
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(User::Table)
+                    .columns([User::Id, User::Name, User::Password])
+                    .values_panic([
+                        Uuid::new_v4().into(),
+                        "admin".into(),
+                        password_hash.into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
         Ok(())
     }
 }
@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::extension::postgres::Type;
+use sea_orm_migration::sea_orm::DbBackend;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if let DbBackend::Postgres = manager.get_database_backend() {
+            manager
+                .create_type(
+                    Type::create()
+                        .as_enum(UserRole::Table)
+                        .values([UserRole::Admin, UserRole::User])
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column_if_not_exists(
+                        enumeration(
+                            User::Role,
+                            UserRole::Table,
+                            [UserRole::Admin, UserRole::User],
+                        )
+                        .default(Expr::val("user").as_enum(UserRole::Table)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 初始化时创建的管理员账户拥有全部权限
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(User::Table)
+                    .value(User::Role, Expr::val("admin").as_enum(UserRole::Table))
+                    .and_where(Expr::col(User::Name).eq("admin"))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Name,
+    Role,
+}
+
+#[derive(DeriveIden)]
+enum UserRole {
+    Table,
+    Admin,
+    User,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AnalysisResult::Table)
+                    .if_not_exists()
+                    .col(pk_uuid(AnalysisResult::Id).default(Uuid::new_v4()))
+                    .col(uuid(AnalysisResult::GroupId))
+                    .col(string(AnalysisResult::Analyzer))
+                    .col(json_binary(AnalysisResult::Result))
+                    .col(
+                        timestamp_with_time_zone(AnalysisResult::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_analysis_result_group_id")
+                    .table(AnalysisResult::Table)
+                    .col(AnalysisResult::GroupId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AnalysisResult {
+    Table,
+    Id,
+    GroupId,
+    Analyzer,
+    Result,
+    CreatedAt,
+}
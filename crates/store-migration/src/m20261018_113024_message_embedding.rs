@@ -0,0 +1,62 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MessageEmbedding::Table)
+                    .if_not_exists()
+                    .col(uuid(MessageEmbedding::MessageId).primary_key())
+                    .col(uuid(MessageEmbedding::SceneId))
+                    .col(integer(MessageEmbedding::Dimension))
+                    .col(json_binary(MessageEmbedding::Embedding))
+                    .col(
+                        timestamp_with_time_zone(MessageEmbedding::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_embedding_message_id")
+                            .from(MessageEmbedding::Table, MessageEmbedding::MessageId)
+                            .to(Message::Table, Message::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_message_embedding_scene_id")
+                    .table(MessageEmbedding::Table)
+                    .col(MessageEmbedding::SceneId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Message {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum MessageEmbedding {
+    Table,
+    MessageId,
+    SceneId,
+    Dimension,
+    Embedding,
+    CreatedAt,
+}
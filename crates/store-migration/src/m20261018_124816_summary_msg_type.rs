@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::extension::postgres::Type;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // sqlite 中枚举以文本存储，仅需扩展 postgres 枚举类型
+        if let DbBackend::Postgres = manager.get_database_backend() {
+            manager
+                .alter_type(
+                    Type::alter()
+                        .name(MsgType::Table)
+                        .add_value(MsgType::Summary)
+                        .if_not_exists()
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum MsgType {
+    Table,
+    Summary,
+}
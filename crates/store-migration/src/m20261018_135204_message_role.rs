@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::extension::postgres::Type;
+use sea_orm_migration::sea_orm::DbBackend;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_database_backend();
+        if let DbBackend::Postgres = backend {
+            manager
+                .create_type(
+                    Type::create()
+                        .as_enum(MessageRole::Table)
+                        .values([
+                            MessageRole::User,
+                            MessageRole::Assistant,
+                            MessageRole::System,
+                        ])
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column_if_not_exists(
+                        enumeration(
+                            Message::Role,
+                            MessageRole::Table,
+                            [
+                                MessageRole::User,
+                                MessageRole::Assistant,
+                                MessageRole::System,
+                            ],
+                        )
+                        .default(Expr::val("user").as_enum(MessageRole::Table)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有的命令回复由助手发出，摘要由系统生成
+        let (is_command_reply, is_summary) = match backend {
+            DbBackend::Postgres => (
+                "metadata->>'source' = 'command'",
+                "msg_type::text = 'summary'",
+            ),
+            _ => (
+                "json_extract(metadata, '$.source') = 'command'",
+                "msg_type = 'summary'",
+            ),
+        };
+        for (role, condition) in [("assistant", is_command_reply), ("system", is_summary)] {
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(Message::Table)
+                        .value(Message::Role, Expr::val(role).as_enum(MessageRole::Table))
+                        .and_where(Expr::cust(condition))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Message {
+    Table,
+    Role,
+}
+
+#[derive(DeriveIden)]
+enum MessageRole {
+    Table,
+    User,
+    Assistant,
+    System,
+}
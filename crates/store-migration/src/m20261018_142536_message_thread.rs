@@ -0,0 +1,126 @@
+use sea_orm_migration::prelude::extension::postgres::Type;
+use sea_orm_migration::prelude::prelude::serde_json::Value as Json;
+use sea_orm_migration::sea_orm::DbBackend;
+use sea_orm_migration::{prelude::*, schema::*};
+use uuid::Uuid;
+
+/// 回填元数据时每批读取的消息数量
+const BACKFILL_BATCH_SIZE: u64 = 500;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if let DbBackend::Postgres = manager.get_database_backend() {
+            for value in [MessageRole::Device, MessageRole::Tool] {
+                manager
+                    .alter_type(
+                        Type::alter()
+                            .name(MessageRole::Table)
+                            .add_value(value)
+                            .if_not_exists()
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column_if_not_exists(string_null(Message::Source))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column_if_not_exists(uuid_null(Message::ReplyToGroupId))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_message_reply_to_group_id")
+                    .table(Message::Table)
+                    .col(Message::ReplyToGroupId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 将此前保存在元数据中的来源与回复关系迁移到独立字段，无法转换的值保留在元数据中
+        let db = manager.get_connection();
+        let mut last_id: Option<Uuid> = None;
+        loop {
+            let mut query = Query::select()
+                .columns([Message::Id, Message::Metadata])
+                .from(Message::Table)
+                .order_by(Message::Id, Order::Asc)
+                .limit(BACKFILL_BATCH_SIZE)
+                .to_owned();
+            if let Some(last_id) = last_id {
+                query.and_where(Expr::col(Message::Id).gt(last_id));
+            }
+            let rows = db.query_all(&query).await?;
+            for row in &rows {
+                let id: Uuid = row.try_get("", "id")?;
+                last_id = Some(id);
+                let Json::Object(mut metadata) = row.try_get::<Json>("", "metadata")? else {
+                    continue;
+                };
+                let source = metadata
+                    .get("source")
+                    .and_then(Json::as_str)
+                    .map(String::from);
+                let reply_to_group_id = metadata
+                    .get("reply_to_group_id")
+                    .and_then(Json::as_str)
+                    .and_then(|id| Uuid::parse_str(id).ok());
+                if source.is_none() && reply_to_group_id.is_none() {
+                    continue;
+                }
+                let mut update = Query::update()
+                    .table(Message::Table)
+                    .and_where(Expr::col(Message::Id).eq(id))
+                    .to_owned();
+                if let Some(source) = source {
+                    metadata.remove("source");
+                    update.value(Message::Source, source);
+                }
+                if let Some(reply_to_group_id) = reply_to_group_id {
+                    metadata.remove("reply_to_group_id");
+                    update.value(Message::ReplyToGroupId, reply_to_group_id);
+                }
+                update.value(Message::Metadata, Json::Object(metadata));
+                manager.exec_stmt(update).await?;
+            }
+            if (rows.len() as u64) < BACKFILL_BATCH_SIZE {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Message {
+    Table,
+    Id,
+    Metadata,
+    Source,
+    ReplyToGroupId,
+}
+
+#[derive(DeriveIden)]
+enum MessageRole {
+    Table,
+    Device,
+    Tool,
+}
@@ -0,0 +1,33 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 场景消息分页按创建时间与 ID 排序
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_message_scene_id_created_at")
+                    .table(Message::Table)
+                    .col(Message::SceneId)
+                    .col(Message::CreatedAt)
+                    .col(Message::Id)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Message {
+    Table,
+    Id,
+    SceneId,
+    CreatedAt,
+}
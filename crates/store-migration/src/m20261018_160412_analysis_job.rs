@@ -0,0 +1,103 @@
+use sea_orm_migration::prelude::extension::postgres::Type;
+use sea_orm_migration::sea_orm::DbBackend;
+use sea_orm_migration::{prelude::*, schema::*};
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if let DbBackend::Postgres = manager.get_database_backend() {
+            manager
+                .create_type(
+                    Type::create()
+                        .as_enum(AnalysisJobStatus::Table)
+                        .values([
+                            AnalysisJobStatus::Pending,
+                            AnalysisJobStatus::Running,
+                            AnalysisJobStatus::Succeeded,
+                            AnalysisJobStatus::Failed,
+                        ])
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(AnalysisJob::Table)
+                    .if_not_exists()
+                    .col(pk_uuid(AnalysisJob::Id).default(Uuid::new_v4()))
+                    .col(uuid_uniq(AnalysisJob::GroupId))
+                    .col(
+                        enumeration(
+                            AnalysisJob::Status,
+                            AnalysisJobStatus::Table,
+                            [
+                                AnalysisJobStatus::Pending,
+                                AnalysisJobStatus::Running,
+                                AnalysisJobStatus::Succeeded,
+                                AnalysisJobStatus::Failed,
+                            ],
+                        )
+                        .default(Expr::val("pending").as_enum(AnalysisJobStatus::Table)),
+                    )
+                    .col(integer(AnalysisJob::Attempts).default(0))
+                    .col(text_null(AnalysisJob::LastError))
+                    .col(json_binary(AnalysisJob::CompletedAnalyzers))
+                    .col(
+                        timestamp_with_time_zone(AnalysisJob::NextRunAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(AnalysisJob::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(AnalysisJob::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_analysis_job_status_next_run_at")
+                    .table(AnalysisJob::Table)
+                    .col(AnalysisJob::Status)
+                    .col(AnalysisJob::NextRunAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AnalysisJob {
+    Table,
+    Id,
+    GroupId,
+    Status,
+    Attempts,
+    LastError,
+    CompletedAnalyzers,
+    NextRunAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum AnalysisJobStatus {
+    Table,
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
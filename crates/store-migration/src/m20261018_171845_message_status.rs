@@ -0,0 +1,155 @@
+use sea_orm_migration::prelude::extension::postgres::Type;
+use sea_orm_migration::sea_orm::DbBackend;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if let DbBackend::Postgres = manager.get_database_backend() {
+            manager
+                .create_type(
+                    Type::create()
+                        .as_enum(MessageStatus::Table)
+                        .values([
+                            MessageStatus::Pending,
+                            MessageStatus::Analyzing,
+                            MessageStatus::Handled,
+                            MessageStatus::Ignored,
+                            MessageStatus::Failed,
+                        ])
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column_if_not_exists(
+                        enumeration(
+                            Message::Status,
+                            MessageStatus::Table,
+                            [
+                                MessageStatus::Pending,
+                                MessageStatus::Analyzing,
+                                MessageStatus::Handled,
+                                MessageStatus::Ignored,
+                                MessageStatus::Failed,
+                            ],
+                        )
+                        .default(Expr::val("pending").as_enum(MessageStatus::Table)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column_if_not_exists(string_null(Message::HandledBy))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column_if_not_exists(timestamp_with_time_zone_null(Message::HandledAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_message_scene_id_status")
+                    .table(Message::Table)
+                    .col(Message::SceneId)
+                    .col(Message::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 根据已有的分析任务结果回填消息状态，未经分析但已处理的消息视为忽略
+        for (job_status, status) in [("succeeded", "handled"), ("failed", "failed")] {
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(Message::Table)
+                        .value(
+                            Message::Status,
+                            Expr::val(status).as_enum(MessageStatus::Table),
+                        )
+                        .value(Message::HandledAt, Expr::col(Message::UpdatedAt))
+                        .and_where(
+                            Expr::col(Message::GroupId).in_subquery(
+                                Query::select()
+                                    .column(AnalysisJob::GroupId)
+                                    .from(AnalysisJob::Table)
+                                    .and_where(Expr::col(AnalysisJob::Status).eq(
+                                        Expr::val(job_status).as_enum(AnalysisJobStatus::Table),
+                                    ))
+                                    .to_owned(),
+                            ),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Message::Table)
+                    .value(
+                        Message::Status,
+                        Expr::val("ignored").as_enum(MessageStatus::Table),
+                    )
+                    .value(Message::HandledAt, Expr::col(Message::UpdatedAt))
+                    .and_where(Expr::col(Message::IsProcessed).eq(true))
+                    .and_where(Expr::col(Message::HandledAt).is_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Message {
+    Table,
+    SceneId,
+    GroupId,
+    IsProcessed,
+    UpdatedAt,
+    Status,
+    HandledBy,
+    HandledAt,
+}
+
+#[derive(DeriveIden)]
+enum MessageStatus {
+    Table,
+    Pending,
+    Analyzing,
+    Handled,
+    Ignored,
+    Failed,
+}
+
+#[derive(DeriveIden)]
+enum AnalysisJob {
+    Table,
+    GroupId,
+    Status,
+}
+
+#[derive(DeriveIden)]
+enum AnalysisJobStatus {
+    Table,
+}
@@ -0,0 +1,95 @@
+use sea_orm_migration::prelude::prelude::serde_json::{Map, Value as Json};
+use sea_orm_migration::prelude::*;
+use uuid::Uuid;
+
+/// 版本 1 中定义的场景元数据字段，其余字段移入 `extra`
+const KNOWN_FIELDS: [&str; 7] = [
+    "description",
+    "system_prompt",
+    "model",
+    "analyzers",
+    "reply_target",
+    "retention",
+    "extra",
+];
+
+/// 旧的非对象 `extra` 值在升级后的字段名
+const LEGACY_EXTRA_KEY: &str = "legacy_extra";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 为未标记版本的场景元数据补全版本号与描述，不合法的字段值保持原样以便后续报告
+        let db = manager.get_connection();
+        let rows = db
+            .query_all(
+                &Query::select()
+                    .columns([Scene::Id, Scene::Metadata])
+                    .from(Scene::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for row in rows {
+            let id: Uuid = row.try_get("", "id")?;
+            let metadata = match row.try_get::<Json>("", "metadata")? {
+                Json::Object(metadata) if metadata.contains_key("version") => continue,
+                Json::Object(metadata) => metadata,
+                Json::String(description) => {
+                    Map::from_iter([("description".to_string(), Json::String(description))])
+                }
+                _ => Map::new(),
+            };
+
+            let mut upgraded = Map::new();
+            let mut extra = Map::new();
+            for (key, value) in metadata {
+                if KNOWN_FIELDS.contains(&key.as_str()) {
+                    upgraded.insert(key, value);
+                } else {
+                    extra.insert(key, value);
+                }
+            }
+            match upgraded.remove("extra") {
+                Some(Json::Object(mut existing)) => {
+                    existing.extend(extra);
+                    upgraded.insert("extra".to_string(), Json::Object(existing));
+                }
+                // 旧的非对象 `extra` 不丢弃，移入新的 `extra` 对象
+                Some(legacy) => {
+                    extra.insert(LEGACY_EXTRA_KEY.to_string(), legacy);
+                    upgraded.insert("extra".to_string(), Json::Object(extra));
+                }
+                None if !extra.is_empty() => {
+                    upgraded.insert("extra".to_string(), Json::Object(extra));
+                }
+                None => {}
+            }
+            upgraded
+                .entry("description")
+                .or_insert_with(|| Json::String(String::new()));
+            upgraded.insert("version".to_string(), Json::from(1));
+
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(Scene::Table)
+                        .value(Scene::Metadata, Json::Object(upgraded))
+                        .and_where(Expr::col(Scene::Id).eq(id))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Scene {
+    Table,
+    Id,
+    Metadata,
+}
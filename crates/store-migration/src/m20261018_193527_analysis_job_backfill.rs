@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::prelude::serde_json::Value as Json;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::prelude::DateTimeWithTimeZone;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 为引入分析任务前已存在的未处理消息组创建任务，
+        // 任务创建时间取消息组最早的消息创建时间，保持场景内的执行顺序
+        let db = manager.get_connection();
+        let rows = db
+            .query_all(
+                &Query::select()
+                    .columns([Message::GroupId, Message::CreatedAt])
+                    .from(Message::Table)
+                    .and_where(Expr::col(Message::IsProcessed).eq(false))
+                    .and_where(
+                        Expr::col(Message::Status)
+                            .eq(Expr::val("pending").as_enum(MessageStatus::Table)),
+                    )
+                    .and_where(
+                        Expr::col(Message::GroupId).not_in_subquery(
+                            Query::select()
+                                .column(AnalysisJob::GroupId)
+                                .from(AnalysisJob::Table)
+                                .to_owned(),
+                        ),
+                    )
+                    .order_by(Message::CreatedAt, Order::Asc)
+                    .to_owned(),
+            )
+            .await?;
+        let mut group_ids = HashSet::new();
+        for row in rows {
+            let group_id: Uuid = row.try_get("", "group_id")?;
+            if !group_ids.insert(group_id) {
+                continue;
+            }
+            let created_at: DateTimeWithTimeZone = row.try_get("", "created_at")?;
+            manager
+                .exec_stmt(
+                    Query::insert()
+                        .into_table(AnalysisJob::Table)
+                        .columns([
+                            AnalysisJob::Id,
+                            AnalysisJob::GroupId,
+                            AnalysisJob::CompletedAnalyzers,
+                            AnalysisJob::CreatedAt,
+                        ])
+                        .values_panic([
+                            Uuid::new_v4().into(),
+                            group_id.into(),
+                            Json::Array(Vec::new()).into(),
+                            created_at.into(),
+                        ])
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Message {
+    Table,
+    GroupId,
+    IsProcessed,
+    Status,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum MessageStatus {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum AnalysisJob {
+    Table,
+    Id,
+    GroupId,
+    CompletedAnalyzers,
+    CreatedAt,
+}
@@ -0,0 +1,270 @@
+use crate::StoreError;
+use chrono::Utc;
+pub use nihility_store_entity::analysis_job::Model as AnalysisJobModel;
+use nihility_store_entity::prelude::{AnalysisJob, Message};
+pub use nihility_store_entity::sea_orm_active_enums::AnalysisJobStatus;
+use nihility_store_entity::{analysis_job, message};
+use sea_orm::prelude::{DateTimeWithTimeZone, Expr};
+use sea_orm::sea_query::{JoinType, Query, SelectStatement};
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbConn, EntityTrait,
+    ExprTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use uuid::Uuid;
+
+/// 创建消息组的分析任务，任务已存在时直接返回
+pub async fn insert_analysis_job<C: ConnectionTrait>(
+    db: &C,
+    group_id: Uuid,
+) -> Result<analysis_job::Model, StoreError> {
+    if let Some(job) = AnalysisJob::find()
+        .filter(analysis_job::Column::GroupId.eq(group_id))
+        .one(db)
+        .await?
+    {
+        return Ok(job);
+    }
+    let now = Utc::now();
+    let active_model = analysis_job::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        group_id: Set(group_id),
+        status: Set(AnalysisJobStatus::Pending),
+        attempts: Set(0),
+        last_error: Set(None),
+        completed_analyzers: Set(serde_json::json!([])),
+        next_run_at: Set(now.into()),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+    };
+    Ok(active_model.insert(db).await?)
+}
+
+pub async fn find_analysis_job_by_id(
+    db: &DbConn,
+    job_id: Uuid,
+) -> Result<analysis_job::Model, StoreError> {
+    AnalysisJob::find_by_id(job_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| StoreError::NotFound(format!("analysis job not found: {}", job_id)))
+}
+
+/// 获取分析任务，按创建时间倒序排列
+pub async fn find_analysis_jobs(
+    db: &DbConn,
+    status: Option<AnalysisJobStatus>,
+    limit: u64,
+) -> Result<Vec<analysis_job::Model>, StoreError> {
+    let mut select = AnalysisJob::find();
+    if let Some(status) = status {
+        select = select.filter(analysis_job::Column::Status.eq(status));
+    }
+    Ok(select
+        .order_by_desc(analysis_job::Column::CreatedAt)
+        .limit(limit)
+        .all(db)
+        .await?)
+}
+
+/// 获取已到执行时间的待执行任务，按执行时间正序排列
+///
+/// 同一场景只返回最早创建的未完成任务，场景中有更早的已到期或执行中的任务时，
+/// 后续任务等待其完成。等待重试退避的任务不阻塞后续任务，避免场景停滞到退避结束。
+/// `running_scene_ids` 中的场景不返回任务，消息组已被删除的任务不属于任何场景，到期即返回
+pub async fn find_due_analysis_jobs(
+    db: &DbConn,
+    now: DateTimeWithTimeZone,
+    running_scene_ids: &[Uuid],
+    limit: u64,
+) -> Result<Vec<analysis_job::Model>, StoreError> {
+    let mut select = AnalysisJob::find()
+        .filter(analysis_job::Column::Status.eq(AnalysisJobStatus::Pending))
+        .filter(analysis_job::Column::NextRunAt.lte(now))
+        .filter(Expr::not_exists(earlier_unfinished_jobs(now)));
+    if !running_scene_ids.is_empty() {
+        select = select.filter(
+            analysis_job::Column::GroupId.not_in_subquery(
+                Query::select()
+                    .column(message::Column::GroupId)
+                    .from(Message)
+                    .and_where(message::Column::SceneId.is_in(running_scene_ids.iter().copied()))
+                    .to_owned(),
+            ),
+        );
+    }
+    Ok(select
+        .order_by_asc(analysis_job::Column::NextRunAt)
+        .order_by_asc(analysis_job::Column::CreatedAt)
+        .limit(limit)
+        .all(db)
+        .await?)
+}
+
+/// 与外层任务同一场景、创建更早的执行中或已到期的待执行任务
+fn earlier_unfinished_jobs(now: DateTimeWithTimeZone) -> SelectStatement {
+    const EARLIER_JOB: &str = "earlier_job";
+    const EARLIER_MESSAGE: &str = "earlier_message";
+    const JOB_MESSAGE: &str = "job_message";
+    let status = |status: AnalysisJobStatus| {
+        Expr::col((EARLIER_JOB, analysis_job::Column::Status))
+            .eq(Expr::val(status).as_enum(AnalysisJobStatus::name()))
+    };
+    Query::select()
+        .expr(Expr::val(1))
+        .from_as(AnalysisJob, EARLIER_JOB)
+        .join_as(
+            JoinType::InnerJoin,
+            Message,
+            EARLIER_MESSAGE,
+            Expr::col((EARLIER_MESSAGE, message::Column::GroupId))
+                .equals((EARLIER_JOB, analysis_job::Column::GroupId)),
+        )
+        .join_as(
+            JoinType::InnerJoin,
+            Message,
+            JOB_MESSAGE,
+            Expr::col((JOB_MESSAGE, message::Column::SceneId))
+                .equals((EARLIER_MESSAGE, message::Column::SceneId)),
+        )
+        .and_where(
+            Expr::col((JOB_MESSAGE, message::Column::GroupId))
+                .equals((AnalysisJob, analysis_job::Column::GroupId)),
+        )
+        .cond_where(
+            Condition::any()
+                .add(status(AnalysisJobStatus::Running))
+                .add(
+                    status(AnalysisJobStatus::Pending)
+                        .and(Expr::col((EARLIER_JOB, analysis_job::Column::NextRunAt)).lte(now)),
+                ),
+        )
+        .cond_where(
+            Condition::any()
+                .add(
+                    Expr::col((EARLIER_JOB, analysis_job::Column::CreatedAt))
+                        .lt(Expr::col((AnalysisJob, analysis_job::Column::CreatedAt))),
+                )
+                .add(
+                    Expr::col((EARLIER_JOB, analysis_job::Column::CreatedAt))
+                        .equals((AnalysisJob, analysis_job::Column::CreatedAt))
+                        .and(
+                            Expr::col((EARLIER_JOB, analysis_job::Column::Id))
+                                .lt(Expr::col((AnalysisJob, analysis_job::Column::Id))),
+                        ),
+                ),
+        )
+        .to_owned()
+}
+
+/// 获取最早的待执行任务的执行时间
+pub async fn find_next_analysis_job_run_at(
+    db: &DbConn,
+) -> Result<Option<DateTimeWithTimeZone>, StoreError> {
+    Ok(AnalysisJob::find()
+        .filter(analysis_job::Column::Status.eq(AnalysisJobStatus::Pending))
+        .order_by_asc(analysis_job::Column::NextRunAt)
+        .one(db)
+        .await?
+        .map(|job| job.next_run_at))
+}
+
+/// 将执行中的任务重置为待执行，用于启动时恢复中断的任务
+pub async fn reset_running_analysis_jobs(db: &DbConn) -> Result<u64, StoreError> {
+    Ok(AnalysisJob::update_many()
+        .filter(analysis_job::Column::Status.eq(AnalysisJobStatus::Running))
+        .col_expr(
+            analysis_job::Column::Status,
+            Expr::value(AnalysisJobStatus::Pending),
+        )
+        .col_expr(
+            analysis_job::Column::UpdatedAt,
+            Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+        )
+        .exec(db)
+        .await?
+        .rows_affected)
+}
+
+/// 标记任务开始执行并增加尝试次数
+pub async fn start_analysis_job(
+    db: &DbConn,
+    job: analysis_job::Model,
+) -> Result<analysis_job::Model, StoreError> {
+    let attempts = job.attempts + 1;
+    let mut active_model: analysis_job::ActiveModel = job.into();
+    active_model.status = Set(AnalysisJobStatus::Running);
+    active_model.attempts = Set(attempts);
+    active_model.updated_at = Set(Utc::now().into());
+    Ok(active_model.update(db).await?)
+}
+
+/// 更新任务中已完成的分析器列表
+pub async fn update_analysis_job_completed(
+    db: &DbConn,
+    job_id: Uuid,
+    completed_analyzers: &[String],
+) -> Result<(), StoreError> {
+    AnalysisJob::update_many()
+        .filter(analysis_job::Column::Id.eq(job_id))
+        .col_expr(
+            analysis_job::Column::CompletedAnalyzers,
+            Expr::value(serde_json::json!(completed_analyzers)),
+        )
+        .col_expr(
+            analysis_job::Column::UpdatedAt,
+            Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// 结束一次任务执行
+///
+/// `next_run_at` 不为空时任务重新进入待执行状态，用于失败后的重试
+pub async fn finish_analysis_job(
+    db: &DbConn,
+    job_id: Uuid,
+    status: AnalysisJobStatus,
+    last_error: Option<String>,
+    next_run_at: Option<DateTimeWithTimeZone>,
+) -> Result<analysis_job::Model, StoreError> {
+    let job = find_analysis_job_by_id(db, job_id).await?;
+    let now = Utc::now();
+    let mut active_model: analysis_job::ActiveModel = job.into();
+    active_model.status = Set(status);
+    active_model.last_error = Set(last_error);
+    if let Some(next_run_at) = next_run_at {
+        active_model.next_run_at = Set(next_run_at);
+    }
+    active_model.updated_at = Set(now.into());
+    Ok(active_model.update(db).await?)
+}
+
+/// 删除指定时间之前成功完成的任务，返回删除的数量
+pub async fn delete_succeeded_analysis_jobs(
+    db: &DbConn,
+    before: DateTimeWithTimeZone,
+) -> Result<u64, StoreError> {
+    Ok(AnalysisJob::delete_many()
+        .filter(analysis_job::Column::Status.eq(AnalysisJobStatus::Succeeded))
+        .filter(analysis_job::Column::UpdatedAt.lt(before))
+        .exec(db)
+        .await?
+        .rows_affected)
+}
+
+/// 手动重试任务，重置尝试次数并立即执行
+pub async fn retry_analysis_job(
+    db: &DbConn,
+    job_id: Uuid,
+) -> Result<analysis_job::Model, StoreError> {
+    let job = find_analysis_job_by_id(db, job_id).await?;
+    let now = Utc::now();
+    let mut active_model: analysis_job::ActiveModel = job.into();
+    active_model.status = Set(AnalysisJobStatus::Pending);
+    active_model.attempts = Set(0);
+    active_model.next_run_at = Set(now.into());
+    active_model.updated_at = Set(now.into());
+    Ok(active_model.update(db).await?)
+}
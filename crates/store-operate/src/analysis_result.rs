@@ -0,0 +1,52 @@
+use crate::StoreError;
+use chrono::Utc;
+use nihility_store_entity::analysis_result;
+use nihility_store_entity::prelude::AnalysisResult;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DbConn, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+pub async fn insert_analysis_result(
+    db: &DbConn,
+    group_id: Uuid,
+    analyzer: &str,
+    result: serde_json::Value,
+) -> Result<analysis_result::Model, StoreError> {
+    let active_model = analysis_result::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        group_id: Set(group_id),
+        analyzer: Set(analyzer.to_string()),
+        result: Set(result),
+        created_at: Set(Utc::now().into()),
+    };
+    Ok(active_model.insert(db).await?)
+}
+
+pub async fn find_analysis_results_by_group_id(
+    db: &DbConn,
+    group_id: Uuid,
+) -> Result<Vec<analysis_result::Model>, StoreError> {
+    Ok(AnalysisResult::find()
+        .filter(analysis_result::Column::GroupId.eq(group_id))
+        .order_by_asc(analysis_result::Column::CreatedAt)
+        .all(db)
+        .await?)
+}
+
+pub async fn find_latest_analysis_result(
+    db: &DbConn,
+    group_id: Uuid,
+    analyzer: &str,
+) -> Result<analysis_result::Model, StoreError> {
+    AnalysisResult::find()
+        .filter(analysis_result::Column::GroupId.eq(group_id))
+        .filter(analysis_result::Column::Analyzer.eq(analyzer))
+        .order_by_desc(analysis_result::Column::CreatedAt)
+        .one(db)
+        .await?
+        .ok_or_else(|| {
+            StoreError::NotFound(format!(
+                "analysis result of {} for group: {}",
+                analyzer, group_id
+            ))
+        })
+}
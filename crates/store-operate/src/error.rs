@@ -8,6 +8,9 @@ pub enum StoreError {
     #[error("Record not found: {0}")]
     NotFound(String),
 
+    #[error("Invalid operation: {0}")]
+    InvalidOperation(String),
+
     #[error("Invalid password hash: {0}")]
     PasswordHash(argon2::password_hash::Error),
 }
@@ -3,7 +3,7 @@ use chrono::Utc;
 use nihility_store_entity::html_pages;
 use nihility_store_entity::prelude::HtmlPages;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DbConn, EntityTrait, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbConn, EntityTrait, QueryFilter, Set,
 };
 use uuid::Uuid;
 
@@ -30,8 +30,19 @@ pub async fn find_by_id(db: &DbConn, id: &Uuid) -> Result<html_pages::Model, Sto
     }
 }
 
-pub async fn create(
-    db: &DbConn,
+pub async fn find_by_path(db: &DbConn, path: &str) -> Result<html_pages::Model, StoreError> {
+    match HtmlPages::find()
+        .filter(html_pages::Column::Path.eq(path))
+        .one(db)
+        .await?
+    {
+        None => Err(StoreError::NotFound(format!("html page: {}", path))),
+        Some(record) => Ok(record),
+    }
+}
+
+pub async fn create<C: ConnectionTrait>(
+    db: &C,
     path: String,
     html: String,
 ) -> Result<html_pages::Model, StoreError> {
@@ -1,6 +1,9 @@
+pub mod analysis_job;
+pub mod analysis_result;
 pub mod error;
 pub mod html_page;
 pub mod message;
+pub mod message_embedding;
 pub mod module_config;
 pub mod scene;
 pub mod user;
@@ -1,32 +1,91 @@
 use crate::StoreError;
 use chrono::Utc;
 use nihility_store_entity::message;
+pub use nihility_store_entity::message::Model as MessageModel;
 use nihility_store_entity::prelude::Message;
-pub use nihility_store_entity::sea_orm_active_enums::MsgType;
-use sea_orm::prelude::Expr;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DbConn, EntityTrait, QueryFilter, Set};
+pub use nihility_store_entity::sea_orm_active_enums::{MessageRole, MessageStatus, MsgType};
+use sea_orm::prelude::{DateTimeWithTimeZone, Expr};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbConn, EntityTrait,
+    FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
 use uuid::Uuid;
 
-pub async fn insert_message(
-    db: &DbConn,
-    scene_id: Uuid,
-    msg_type: MsgType,
-    content: serde_json::Value,
-    metadata: serde_json::Value,
-    group_id: Uuid,
-    is_processed: bool,
+/// 新消息
+#[derive(Debug, Clone)]
+pub struct NewMessage {
+    pub scene_id: Uuid,
+    pub msg_type: MsgType,
+    pub role: MessageRole,
+    /// 来源标识，例如设备 ID、用户名或模块名称
+    pub source: Option<String>,
+    /// 回复的消息组 ID
+    pub reply_to_group_id: Option<Uuid>,
+    pub content: serde_json::Value,
+    pub metadata: serde_json::Value,
+    pub group_id: Uuid,
+    pub is_processed: bool,
+}
+
+pub async fn insert_message<C: ConnectionTrait>(
+    db: &C,
+    msg: NewMessage,
 ) -> Result<message::Model, StoreError> {
     let now = Utc::now();
     let active_model = message::ActiveModel {
         id: Set(Uuid::new_v4()),
-        scene_id: Set(scene_id),
-        msg_type: Set(msg_type),
-        content: Set(content),
-        metadata: Set(metadata),
-        is_processed: Set(is_processed),
+        scene_id: Set(msg.scene_id),
+        msg_type: Set(msg.msg_type),
+        role: Set(msg.role),
+        source: Set(msg.source),
+        reply_to_group_id: Set(msg.reply_to_group_id),
+        content: Set(msg.content),
+        metadata: Set(msg.metadata),
+        is_processed: Set(msg.is_processed),
+        // 写入时已处理的消息不会进入分析链
+        status: Set(if msg.is_processed {
+            MessageStatus::Ignored
+        } else {
+            MessageStatus::Pending
+        }),
+        handled_by: Set(None),
+        handled_at: Set(msg.is_processed.then(|| now.into())),
         created_at: Set(now.into()),
         updated_at: Set(now.into()),
-        group_id: Set(group_id),
+        group_id: Set(msg.group_id),
+    };
+    Ok(active_model.insert(db).await?)
+}
+
+/// 写入导入的历史消息，保留原始的处理状态与创建时间
+pub async fn insert_imported_message<C: ConnectionTrait>(
+    db: &C,
+    msg: NewMessage,
+    status: MessageStatus,
+    handled_by: Option<String>,
+    created_at: DateTimeWithTimeZone,
+) -> Result<message::Model, StoreError> {
+    let now = Utc::now();
+    let handled_at = match status {
+        MessageStatus::Pending | MessageStatus::Analyzing => None,
+        _ => Some(now.into()),
+    };
+    let active_model = message::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        scene_id: Set(msg.scene_id),
+        msg_type: Set(msg.msg_type),
+        role: Set(msg.role),
+        source: Set(msg.source),
+        reply_to_group_id: Set(msg.reply_to_group_id),
+        content: Set(msg.content),
+        metadata: Set(msg.metadata),
+        is_processed: Set(msg.is_processed),
+        status: Set(status),
+        handled_by: Set(handled_by),
+        handled_at: Set(handled_at),
+        created_at: Set(created_at),
+        updated_at: Set(now.into()),
+        group_id: Set(msg.group_id),
     };
     Ok(active_model.insert(db).await?)
 }
@@ -51,6 +110,18 @@ pub async fn find_message_by_group_id(
         .await?)
 }
 
+/// 获取回复指定消息组的消息，按创建时间正序排列
+pub async fn find_messages_by_reply_to_group_id(
+    db: &DbConn,
+    group_id: Uuid,
+) -> Result<Vec<message::Model>, StoreError> {
+    Ok(Message::find()
+        .filter(message::Column::ReplyToGroupId.eq(group_id))
+        .order_by_asc(message::Column::CreatedAt)
+        .all(db)
+        .await?)
+}
+
 pub async fn find_messages_by_scene_id(
     db: &DbConn,
     scene_id: Uuid,
@@ -62,6 +133,18 @@ pub async fn find_messages_by_scene_id(
     Ok(messages)
 }
 
+/// 获取多个场景中的全部消息，按创建时间正序排列
+pub async fn find_messages_by_scene_ids(
+    db: &DbConn,
+    scene_ids: &[Uuid],
+) -> Result<Vec<message::Model>, StoreError> {
+    Ok(Message::find()
+        .filter(message::Column::SceneId.is_in(scene_ids.to_vec()))
+        .order_by_asc(message::Column::CreatedAt)
+        .all(db)
+        .await?)
+}
+
 pub async fn find_unprocessed_messages_by_scene_ids(
     db: &DbConn,
     scene_ids: &[Uuid],
@@ -74,15 +157,327 @@ pub async fn find_unprocessed_messages_by_scene_ids(
     Ok(messages)
 }
 
-pub async fn update_message_processed(
+/// 获取场景中最近的未处理消息，按创建时间倒序排列
+pub async fn find_recent_unprocessed_messages_by_scene_ids(
     db: &DbConn,
-    group_id: Uuid,
+    scene_ids: &[Uuid],
+    limit: u64,
+) -> Result<Vec<message::Model>, StoreError> {
+    let messages = Message::find()
+        .filter(message::Column::SceneId.is_in(scene_ids.to_vec()))
+        .filter(message::Column::IsProcessed.eq(false))
+        .order_by_desc(message::Column::CreatedAt)
+        .limit(limit)
+        .all(db)
+        .await?;
+    Ok(messages)
+}
+
+/// 获取场景中最近的消息，按创建时间倒序排列
+pub async fn find_recent_messages_by_scene_ids(
+    db: &DbConn,
+    scene_ids: &[Uuid],
+    limit: u64,
+) -> Result<Vec<message::Model>, StoreError> {
+    let messages = Message::find()
+        .filter(message::Column::SceneId.is_in(scene_ids.to_vec()))
+        .order_by_desc(message::Column::CreatedAt)
+        .limit(limit)
+        .all(db)
+        .await?;
+    Ok(messages)
+}
+
+pub async fn find_latest_message_by_scene_id_and_type(
+    db: &DbConn,
+    scene_id: Uuid,
+    msg_type: MsgType,
+) -> Result<Option<message::Model>, StoreError> {
+    Ok(Message::find()
+        .filter(message::Column::SceneId.eq(scene_id))
+        .filter(message::Column::MsgType.eq(msg_type))
+        .order_by_desc(message::Column::CreatedAt)
+        .one(db)
+        .await?)
+}
+
+/// 获取场景中晚于指定时间的非摘要消息，按创建时间正序排列
+pub async fn find_messages_by_scene_id_since(
+    db: &DbConn,
+    scene_id: Uuid,
+    since: Option<DateTimeWithTimeZone>,
+) -> Result<Vec<message::Model>, StoreError> {
+    let mut select = Message::find()
+        .filter(message::Column::SceneId.eq(scene_id))
+        .filter(message::Column::MsgType.ne(MsgType::Summary));
+    if let Some(since) = since {
+        select = select.filter(message::Column::CreatedAt.gt(since));
+    }
+    Ok(select
+        .order_by_asc(message::Column::CreatedAt)
+        .all(db)
+        .await?)
+}
+
+/// 消息分页查询条件，未设置的条件不参与过滤
+#[derive(Debug, Clone, Default)]
+pub struct MessageFilter {
+    pub scene_ids: Vec<Uuid>,
+    pub msg_type: Option<MsgType>,
+    pub role: Option<MessageRole>,
+    pub is_processed: Option<bool>,
+    pub status: Option<MessageStatus>,
+    /// 创建时间下界（包含）
+    pub since: Option<DateTimeWithTimeZone>,
+    /// 创建时间上界（不包含）
+    pub until: Option<DateTimeWithTimeZone>,
+}
+
+/// 分页游标，为上一页最后一条消息的创建时间与 ID
+///
+/// 按创建时间、ID 的顺序比较
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MessageCursor {
+    pub created_at: DateTimeWithTimeZone,
+    pub id: Uuid,
+}
+
+/// 按创建时间分页查询消息，创建时间相同时以 ID 排序
+///
+/// 返回当前页消息及下一页游标，没有更多消息时游标为 None
+pub async fn find_messages_page(
+    db: &DbConn,
+    filter: &MessageFilter,
+    cursor: Option<MessageCursor>,
+    ascending: bool,
+    limit: u64,
+) -> Result<(Vec<message::Model>, Option<MessageCursor>), StoreError> {
+    let mut select =
+        Message::find().filter(message::Column::SceneId.is_in(filter.scene_ids.clone()));
+    if let Some(msg_type) = &filter.msg_type {
+        select = select.filter(message::Column::MsgType.eq(msg_type.clone()));
+    }
+    if let Some(role) = &filter.role {
+        select = select.filter(message::Column::Role.eq(role.clone()));
+    }
+    if let Some(is_processed) = filter.is_processed {
+        select = select.filter(message::Column::IsProcessed.eq(is_processed));
+    }
+    if let Some(status) = &filter.status {
+        select = select.filter(message::Column::Status.eq(status.clone()));
+    }
+    if let Some(since) = filter.since {
+        select = select.filter(message::Column::CreatedAt.gte(since));
+    }
+    if let Some(until) = filter.until {
+        select = select.filter(message::Column::CreatedAt.lt(until));
+    }
+    if let Some(cursor) = cursor {
+        let (created_at, id) = if ascending {
+            (
+                message::Column::CreatedAt.gt(cursor.created_at),
+                message::Column::Id.gt(cursor.id),
+            )
+        } else {
+            (
+                message::Column::CreatedAt.lt(cursor.created_at),
+                message::Column::Id.lt(cursor.id),
+            )
+        };
+        select = select.filter(
+            Condition::any().add(created_at).add(
+                Condition::all()
+                    .add(message::Column::CreatedAt.eq(cursor.created_at))
+                    .add(id),
+            ),
+        );
+    }
+    select = if ascending {
+        select
+            .order_by_asc(message::Column::CreatedAt)
+            .order_by_asc(message::Column::Id)
+    } else {
+        select
+            .order_by_desc(message::Column::CreatedAt)
+            .order_by_desc(message::Column::Id)
+    };
+
+    // 多取一条用于判断是否存在下一页
+    let mut messages = select.limit(limit + 1).all(db).await?;
+    let next_cursor = if messages.len() as u64 > limit {
+        messages.truncate(limit as usize);
+        messages.last().map(|msg| MessageCursor {
+            created_at: msg.created_at,
+            id: msg.id,
+        })
+    } else {
+        None
+    };
+    Ok((messages, next_cursor))
+}
+
+/// 获取场景中按创建时间、ID 倒序排在第 `offset` 条（从 0 开始）的非摘要消息的位置
+pub async fn find_nth_latest_message_cursor(
+    db: &DbConn,
+    scene_id: Uuid,
+    offset: u64,
+) -> Result<Option<MessageCursor>, StoreError> {
+    Ok(Message::find()
+        .filter(message::Column::SceneId.eq(scene_id))
+        .filter(message::Column::MsgType.ne(MsgType::Summary))
+        .order_by_desc(message::Column::CreatedAt)
+        .order_by_desc(message::Column::Id)
+        .offset(offset)
+        .one(db)
+        .await?
+        .map(|msg| MessageCursor {
+            created_at: msg.created_at,
+            id: msg.id,
+        }))
+}
+
+/// 不晚于指定位置的消息，创建时间相同时以 ID 截断
+fn not_after(until: MessageCursor) -> Condition {
+    Condition::any()
+        .add(message::Column::CreatedAt.lt(until.created_at))
+        .add(
+            Condition::all()
+                .add(message::Column::CreatedAt.eq(until.created_at))
+                .add(message::Column::Id.lte(until.id)),
+        )
+}
+
+/// 统计场景中不晚于指定位置的已处理非摘要消息数量
+pub async fn count_processed_messages_until(
+    db: &DbConn,
+    scene_id: Uuid,
+    until: MessageCursor,
+) -> Result<u64, StoreError> {
+    Ok(Message::find()
+        .filter(message::Column::SceneId.eq(scene_id))
+        .filter(message::Column::MsgType.ne(MsgType::Summary))
+        .filter(message::Column::IsProcessed.eq(true))
+        .filter(not_after(until))
+        .count(db)
+        .await?)
+}
+
+/// 获取场景中不晚于指定位置的已处理非摘要消息，按创建时间正序排列
+pub async fn find_processed_messages_until(
+    db: &DbConn,
+    scene_id: Uuid,
+    until: MessageCursor,
+    limit: u64,
+) -> Result<Vec<message::Model>, StoreError> {
+    Ok(Message::find()
+        .filter(message::Column::SceneId.eq(scene_id))
+        .filter(message::Column::MsgType.ne(MsgType::Summary))
+        .filter(message::Column::IsProcessed.eq(true))
+        .filter(not_after(until))
+        .order_by_asc(message::Column::CreatedAt)
+        .order_by_asc(message::Column::Id)
+        .limit(limit)
+        .all(db)
+        .await?)
+}
+
+pub async fn delete_messages_by_ids(db: &DbConn, message_ids: &[Uuid]) -> Result<u64, StoreError> {
+    Ok(Message::delete_many()
+        .filter(message::Column::Id.is_in(message_ids.to_vec()))
+        .exec(db)
+        .await?
+        .rows_affected)
+}
+
+/// 按场景、处理状态与是否已处理分组的消息数量
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct MessageStatusCount {
+    pub scene_id: Uuid,
+    pub status: MessageStatus,
+    pub is_processed: bool,
+    pub count: i64,
+}
+
+pub async fn count_messages_by_scene_ids(
+    db: &DbConn,
+    scene_ids: &[Uuid],
+) -> Result<Vec<MessageStatusCount>, StoreError> {
+    Ok(Message::find()
+        .select_only()
+        .column(message::Column::SceneId)
+        .column(message::Column::Status)
+        .column(message::Column::IsProcessed)
+        .column_as(message::Column::Id.count(), "count")
+        .filter(message::Column::SceneId.is_in(scene_ids.to_vec()))
+        .group_by(message::Column::SceneId)
+        .group_by(message::Column::Status)
+        .group_by(message::Column::IsProcessed)
+        .into_model::<MessageStatusCount>()
+        .all(db)
+        .await?)
+}
+
+pub async fn update_message_metadata(
+    db: &DbConn,
+    message_id: Uuid,
+    metadata: serde_json::Value,
+) -> Result<(), StoreError> {
+    Message::update_many()
+        .filter(message::Column::Id.eq(message_id))
+        .col_expr(message::Column::Metadata, Expr::value(metadata))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn update_messages_processed(
+    db: &DbConn,
+    message_ids: &[Uuid],
     is_processed: bool,
 ) -> Result<(), StoreError> {
     Message::update_many()
-        .filter(message::Column::GroupId.eq(group_id))
+        .filter(message::Column::Id.is_in(message_ids.to_vec()))
         .col_expr(message::Column::IsProcessed, Expr::value(is_processed))
+        .col_expr(
+            message::Column::UpdatedAt,
+            Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// 更新消息组的处理状态，`handled_by` 为处理该消息组的分析器名称
+pub async fn update_group_status(
+    db: &DbConn,
+    group_id: Uuid,
+    status: MessageStatus,
+    handled_by: Option<&str>,
+) -> Result<(), StoreError> {
+    status_update(status, handled_by)
+        .filter(message::Column::GroupId.eq(group_id))
         .exec(db)
         .await?;
     Ok(())
 }
+
+/// 构建状态更新语句，进入终态时记录处理时间
+fn status_update(status: MessageStatus, handled_by: Option<&str>) -> sea_orm::UpdateMany<Message> {
+    let handled_at = match status {
+        MessageStatus::Pending | MessageStatus::Analyzing => None,
+        MessageStatus::Handled | MessageStatus::Ignored | MessageStatus::Failed => {
+            Some(DateTimeWithTimeZone::from(Utc::now()))
+        }
+    };
+    Message::update_many()
+        .col_expr(message::Column::Status, Expr::value(status))
+        .col_expr(
+            message::Column::HandledBy,
+            Expr::value(handled_by.map(String::from)),
+        )
+        .col_expr(message::Column::HandledAt, Expr::value(handled_at))
+        .col_expr(
+            message::Column::UpdatedAt,
+            Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+        )
+}
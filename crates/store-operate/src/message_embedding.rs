@@ -0,0 +1,164 @@
+use crate::StoreError;
+use chrono::Utc;
+use nihility_store_entity::prelude::{Message, MessageEmbedding};
+use nihility_store_entity::{message, message_embedding};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DbBackend, DbConn, EntityTrait, FromQueryResult, QueryFilter,
+    Set, Statement, Value,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// 向量检索命中的消息
+#[derive(Debug, Clone)]
+pub struct ScoredMessage {
+    pub message: message::Model,
+    /// 余弦相似度
+    pub score: f32,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ScoredRow {
+    message_id: Uuid,
+    score: Option<f64>,
+}
+
+pub async fn insert_message_embedding(
+    db: &DbConn,
+    message_id: Uuid,
+    scene_id: Uuid,
+    embedding: &[f32],
+) -> Result<message_embedding::Model, StoreError> {
+    let active_model = message_embedding::ActiveModel {
+        message_id: Set(message_id),
+        scene_id: Set(scene_id),
+        dimension: Set(embedding.len() as i32),
+        embedding: Set(to_json(embedding)),
+        created_at: Set(Utc::now().into()),
+    };
+    Ok(active_model.insert(db).await?)
+}
+
+/// 按余弦相似度检索消息，仅比较维度一致的向量
+///
+/// postgres 在数据库中计算相似度，sqlite 加载候选向量后在内存中暴力计算
+pub async fn search_messages_by_embedding(
+    db: &DbConn,
+    query: &[f32],
+    scene_ids: Option<&[Uuid]>,
+    top_k: u64,
+) -> Result<Vec<ScoredMessage>, StoreError> {
+    if query.is_empty() || top_k == 0 || scene_ids.is_some_and(|ids| ids.is_empty()) {
+        return Ok(Vec::new());
+    }
+    let ranked = match db.get_database_backend() {
+        DbBackend::Postgres => search_postgres(db, query, scene_ids, top_k).await?,
+        _ => search_brute_force(db, query, scene_ids, top_k).await?,
+    };
+
+    let mut messages = Message::find()
+        .filter(message::Column::Id.is_in(ranked.iter().map(|(id, _)| *id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|m| (m.id, m))
+        .collect::<HashMap<_, _>>();
+    Ok(ranked
+        .into_iter()
+        .filter_map(|(id, score)| {
+            messages
+                .remove(&id)
+                .map(|message| ScoredMessage { message, score })
+        })
+        .collect())
+}
+
+async fn search_postgres(
+    db: &DbConn,
+    query: &[f32],
+    scene_ids: Option<&[Uuid]>,
+    top_k: u64,
+) -> Result<Vec<(Uuid, f32)>, StoreError> {
+    let mut values: Vec<Value> = vec![to_json(query).into(), (query.len() as i32).into()];
+    let scene_filter = match scene_ids {
+        Some(ids) => {
+            let placeholders = ids
+                .iter()
+                .map(|id| {
+                    values.push((*id).into());
+                    format!("${}", values.len())
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("AND e.scene_id IN ({})", placeholders)
+        }
+        None => String::new(),
+    };
+    values.push((top_k as i64).into());
+    let sql = format!(
+        r#"SELECT e.message_id, s.score
+FROM message_embedding e
+CROSS JOIN LATERAL (
+    SELECT SUM(a.v::float8 * b.v::float8)
+        / NULLIF(SQRT(SUM(a.v::float8 * a.v::float8)) * SQRT(SUM(b.v::float8 * b.v::float8)), 0) AS score
+    FROM jsonb_array_elements_text(e.embedding) WITH ORDINALITY AS a(v, i)
+    JOIN jsonb_array_elements_text($1::jsonb) WITH ORDINALITY AS b(v, i) ON a.i = b.i
+) s
+WHERE e.dimension = $2 {}
+ORDER BY s.score DESC NULLS LAST
+LIMIT ${}"#,
+        scene_filter,
+        values.len()
+    );
+    let rows = ScoredRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        values,
+    ))
+    .all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.message_id, row.score.unwrap_or_default() as f32))
+        .collect())
+}
+
+async fn search_brute_force(
+    db: &DbConn,
+    query: &[f32],
+    scene_ids: Option<&[Uuid]>,
+    top_k: u64,
+) -> Result<Vec<(Uuid, f32)>, StoreError> {
+    let mut select = MessageEmbedding::find()
+        .filter(message_embedding::Column::Dimension.eq(query.len() as i32));
+    if let Some(ids) = scene_ids {
+        select = select.filter(message_embedding::Column::SceneId.is_in(ids.to_vec()));
+    }
+    let mut ranked = select
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|row| {
+            let embedding: Vec<f32> = serde_json::from_value(row.embedding).ok()?;
+            Some((row.message_id, cosine_similarity(query, &embedding)))
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(top_k as usize);
+    Ok(ranked)
+}
+
+fn to_json(embedding: &[f32]) -> serde_json::Value {
+    serde_json::Value::Array(embedding.iter().map(|v| (*v).into()).collect())
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    let norm = norm_a.sqrt() * norm_b.sqrt();
+    if norm > 0.0 { dot / norm } else { 0.0 }
+}
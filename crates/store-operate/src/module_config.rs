@@ -2,7 +2,9 @@ use crate::StoreError;
 use chrono::Utc;
 use nihility_store_entity::module_config;
 use nihility_store_entity::prelude::ModuleConfig;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DbConn, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbConn, EntityTrait, QueryFilter, Set,
+};
 use uuid::Uuid;
 
 pub async fn find_by_module_name(
@@ -21,8 +23,8 @@ pub async fn list_all(db: &DbConn) -> Result<Vec<module_config::Model>, StoreErr
     Ok(configs)
 }
 
-pub async fn update_config_value(
-    db: &DbConn,
+pub async fn update_config_value<C: ConnectionTrait>(
+    db: &C,
     id: &Uuid,
     config_value: serde_json::Value,
 ) -> Result<module_config::Model, StoreError> {
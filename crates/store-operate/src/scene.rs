@@ -1,11 +1,20 @@
 use crate::StoreError;
 use chrono::Utc;
-use nihility_store_entity::prelude::Scene;
-use nihility_store_entity::scene;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DbConn, EntityTrait, ModelTrait, QueryFilter, Set};
+use nihility_store_entity::prelude::{Message, MessageEmbedding, Scene};
+pub use nihility_store_entity::scene::Model as SceneModel;
+use nihility_store_entity::{message, message_embedding, scene};
+use sea_orm::prelude::{DateTimeWithTimeZone, Expr};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbBackend, DbConn, EntityTrait,
+    FromQueryResult, PaginatorTrait, QueryFilter, Set, Statement, TransactionTrait,
+};
+use std::collections::HashMap;
 use uuid::Uuid;
 
-pub async fn find_scene_by_id(db: &DbConn, scene_id: Uuid) -> Result<scene::Model, StoreError> {
+pub async fn find_scene_by_id<C: ConnectionTrait>(
+    db: &C,
+    scene_id: Uuid,
+) -> Result<scene::Model, StoreError> {
     Scene::find_by_id(scene_id)
         .one(db)
         .await?
@@ -23,15 +32,30 @@ pub async fn find_scenes_by_parent_id(
     Ok(scenes)
 }
 
-pub async fn insert_scene(
+pub async fn find_scene_by_name(
     db: &DbConn,
+    name: &str,
+) -> Result<Option<scene::Model>, StoreError> {
+    Ok(Scene::find()
+        .filter(scene::Column::Name.eq(name))
+        .one(db)
+        .await?)
+}
+
+pub async fn insert_scene<C: ConnectionTrait>(
+    db: &C,
     name: String,
     parent_id: Option<Uuid>,
     metadata: serde_json::Value,
 ) -> Result<scene::Model, StoreError> {
+    let id = Uuid::new_v4();
+    if let Some(pid) = parent_id {
+        check_parent(db, id, pid).await?;
+    }
+
     let now = Utc::now();
     let active_model = scene::ActiveModel {
-        id: Set(Uuid::new_v4()),
+        id: Set(id),
         name: Set(name),
         parent_id: Set(parent_id),
         metadata: Set(metadata),
@@ -41,17 +65,37 @@ pub async fn insert_scene(
     Ok(active_model.insert(db).await?)
 }
 
-pub async fn update_scene(
-    db: &DbConn,
+/// 内置场景名称，不允许删除或重命名
+pub const PROTECTED_SCENE_NAMES: [&str; 3] = ["base", "virtual", "real"];
+
+/// 删除场景时子场景的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneDeleteMode {
+    /// 删除场景及其全部后代场景
+    Cascade,
+    /// 子场景与消息移动到被删除场景的父场景下
+    Reparent,
+    /// 场景存在子场景或消息时拒绝删除
+    Restrict,
+}
+
+pub async fn update_scene<C: ConnectionTrait>(
+    db: &C,
     id: Uuid,
     name: Option<String>,
     parent_id: Option<Uuid>,
     metadata: Option<serde_json::Value>,
 ) -> Result<scene::Model, StoreError> {
-    let existing = Scene::find_by_id(id)
-        .one(db)
-        .await?
-        .ok_or_else(|| StoreError::NotFound(format!("scene not found: {}", id)))?;
+    let existing = find_scene_by_id(db, id).await?;
+    if let Some(n) = &name
+        && *n != existing.name
+        && PROTECTED_SCENE_NAMES.contains(&existing.name.as_str())
+    {
+        return Err(StoreError::InvalidOperation(format!(
+            "built-in scene cannot be renamed: {}",
+            existing.name
+        )));
+    }
 
     let now = Utc::now();
     let mut active_model: scene::ActiveModel = existing.into();
@@ -59,6 +103,7 @@ pub async fn update_scene(
         active_model.name = Set(n);
     }
     if let Some(pid) = parent_id {
+        check_parent(db, id, pid).await?;
         active_model.parent_id = Set(Some(pid));
     }
     if let Some(m) = metadata {
@@ -69,16 +114,326 @@ pub async fn update_scene(
     Ok(active_model.update(db).await?)
 }
 
-pub async fn delete_scene(db: &DbConn, id: Uuid) -> Result<(), StoreError> {
-    let existing = Scene::find_by_id(id)
-        .one(db)
-        .await?
-        .ok_or_else(|| StoreError::NotFound(format!("scene not found: {}", id)))?;
-    scene::Model::delete(existing, db).await?;
+/// 移动场景到新的父场景下，`parent_id` 为空时移动为根场景
+pub async fn move_scene(
+    db: &DbConn,
+    id: Uuid,
+    parent_id: Option<Uuid>,
+) -> Result<scene::Model, StoreError> {
+    let existing = find_scene_by_id(db, id).await?;
+    if let Some(pid) = parent_id {
+        check_parent(db, id, pid).await?;
+    }
+
+    let mut active_model: scene::ActiveModel = existing.into();
+    active_model.parent_id = Set(parent_id);
+    active_model.updated_at = Set(Utc::now().into());
+    Ok(active_model.update(db).await?)
+}
+
+/// 校验父场景存在，且不是场景自身或其后代场景
+async fn check_parent<C: ConnectionTrait>(
+    db: &C,
+    id: Uuid,
+    parent_id: Uuid,
+) -> Result<(), StoreError> {
+    let ancestors = find_scene_ancestors(db, parent_id).await?;
+    if ancestors.iter().any(|scene| scene.id == id) {
+        return Err(StoreError::InvalidOperation(format!(
+            "scene {} cannot be moved under its own subtree {}",
+            id, parent_id
+        )));
+    }
     Ok(())
 }
 
+/// 场景删除结果
+#[derive(Debug, Clone)]
+pub struct SceneDeletion {
+    /// 被删除的场景 ID，包含级联删除的后代场景
+    pub deleted_ids: Vec<Uuid>,
+    /// 移动到父场景的消息数量
+    pub moved_messages: u64,
+}
+
+/// 删除场景
+pub async fn delete_scene(
+    db: &DbConn,
+    id: Uuid,
+    mode: SceneDeleteMode,
+) -> Result<SceneDeletion, StoreError> {
+    let existing = find_scene_by_id(db, id).await?;
+    let scenes = match mode {
+        SceneDeleteMode::Cascade => find_scene_subtree(db, id, None)
+            .await?
+            .into_iter()
+            .map(|node| node.scene)
+            .collect(),
+        SceneDeleteMode::Reparent | SceneDeleteMode::Restrict => vec![existing.clone()],
+    };
+    if let Some(scene) = scenes
+        .iter()
+        .find(|scene| PROTECTED_SCENE_NAMES.contains(&scene.name.as_str()))
+    {
+        return Err(StoreError::InvalidOperation(format!(
+            "built-in scene cannot be deleted: {}",
+            scene.name
+        )));
+    }
+
+    if mode == SceneDeleteMode::Restrict {
+        let children = Scene::find()
+            .filter(scene::Column::ParentId.eq(id))
+            .count(db)
+            .await?;
+        let messages = Message::find()
+            .filter(message::Column::SceneId.eq(id))
+            .count(db)
+            .await?;
+        if children > 0 || messages > 0 {
+            return Err(StoreError::InvalidOperation(format!(
+                "scene {} is not empty: {} child scenes, {} messages",
+                id, children, messages
+            )));
+        }
+    }
+
+    let ids = scenes.iter().map(|scene| scene.id).collect::<Vec<_>>();
+    let txn = db.begin().await?;
+    let mut moved_messages = 0;
+    if mode == SceneDeleteMode::Reparent {
+        match existing.parent_id {
+            Some(parent_id) => {
+                moved_messages = Message::update_many()
+                    .filter(message::Column::SceneId.eq(id))
+                    .col_expr(message::Column::SceneId, Expr::value(parent_id))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected;
+                MessageEmbedding::update_many()
+                    .filter(message_embedding::Column::SceneId.eq(id))
+                    .col_expr(message_embedding::Column::SceneId, Expr::value(parent_id))
+                    .exec(&txn)
+                    .await?;
+            }
+            None => {
+                let messages = Message::find()
+                    .filter(message::Column::SceneId.eq(id))
+                    .count(&txn)
+                    .await?;
+                if messages > 0 {
+                    return Err(StoreError::InvalidOperation(format!(
+                        "root scene {} has {} messages and no parent to move them to",
+                        id, messages
+                    )));
+                }
+            }
+        }
+        Scene::update_many()
+            .filter(scene::Column::ParentId.eq(id))
+            .col_expr(scene::Column::ParentId, Expr::value(existing.parent_id))
+            .col_expr(
+                scene::Column::UpdatedAt,
+                Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+            )
+            .exec(&txn)
+            .await?;
+    }
+    Scene::delete_many()
+        .filter(scene::Column::Id.is_in(ids.clone()))
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+    Ok(SceneDeletion {
+        deleted_ids: ids,
+        moved_messages,
+    })
+}
+
 pub async fn find_all_scenes(db: &DbConn) -> Result<Vec<scene::Model>, StoreError> {
     let scenes = Scene::find().all(db).await?;
     Ok(scenes)
 }
+
+/// 场景子树中的节点
+#[derive(Debug, Clone)]
+pub struct SceneNode {
+    pub scene: scene::Model,
+    /// 相对子树根场景的深度，根场景为 0
+    pub depth: u32,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct TreeRow {
+    id: Uuid,
+    depth: i32,
+}
+
+/// 获取场景及其全部后代场景 ID，按层级顺序排列
+pub async fn find_scene_subtree_ids(db: &DbConn, root_id: Uuid) -> Result<Vec<Uuid>, StoreError> {
+    Ok(subtree_rows(db, root_id, None)
+        .await?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect())
+}
+
+/// 获取场景子树，按深度排列，同层级按创建时间排列
+///
+/// `max_depth` 限制相对根场景的最大深度，为空时不限制
+pub async fn find_scene_subtree(
+    db: &DbConn,
+    root_id: Uuid,
+    max_depth: Option<u32>,
+) -> Result<Vec<SceneNode>, StoreError> {
+    let rows = subtree_rows(db, root_id, max_depth).await?;
+    let mut scenes = load_scenes(db, rows.iter().map(|(id, _)| *id)).await?;
+    let mut nodes = rows
+        .into_iter()
+        .filter_map(|(id, depth)| scenes.remove(&id).map(|scene| SceneNode { scene, depth }))
+        .collect::<Vec<_>>();
+    nodes.sort_by_key(|node| (node.depth, node.scene.created_at, node.scene.id));
+    Ok(nodes)
+}
+
+/// 获取从场景到根场景的路径，第一个元素为场景自身
+pub async fn find_scene_ancestors<C: ConnectionTrait>(
+    db: &C,
+    scene_id: Uuid,
+) -> Result<Vec<scene::Model>, StoreError> {
+    let ids = match db.get_database_backend() {
+        DbBackend::Postgres => ancestor_ids_postgres(db, scene_id).await?,
+        _ => ancestor_ids_iterative(db, scene_id).await?,
+    };
+    if ids.is_empty() {
+        return Err(StoreError::NotFound(format!(
+            "scene not found: {}",
+            scene_id
+        )));
+    }
+    let mut scenes = load_scenes(db, ids.iter().copied()).await?;
+    Ok(ids.iter().filter_map(|id| scenes.remove(id)).collect())
+}
+
+/// 查询子树的场景 ID 与深度，根场景不存在时返回空列表
+async fn subtree_rows(
+    db: &DbConn,
+    root_id: Uuid,
+    max_depth: Option<u32>,
+) -> Result<Vec<(Uuid, u32)>, StoreError> {
+    match db.get_database_backend() {
+        DbBackend::Postgres => subtree_rows_postgres(db, root_id, max_depth).await,
+        _ => subtree_rows_iterative(db, root_id, max_depth).await,
+    }
+}
+
+async fn subtree_rows_postgres(
+    db: &DbConn,
+    root_id: Uuid,
+    max_depth: Option<u32>,
+) -> Result<Vec<(Uuid, u32)>, StoreError> {
+    // path 记录已访问的场景，防止错误数据形成环导致无限递归
+    let sql = r#"WITH RECURSIVE subtree AS (
+    SELECT id, 0 AS depth, ARRAY[id] AS path FROM scene WHERE id = $1
+    UNION ALL
+    SELECT s.id, t.depth + 1, t.path || s.id
+    FROM scene s
+    JOIN subtree t ON s.parent_id = t.id
+    WHERE NOT s.id = ANY(t.path) AND ($2::int4 IS NULL OR t.depth < $2::int4)
+)
+SELECT id, depth FROM subtree ORDER BY depth"#;
+    let rows = TreeRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        [root_id.into(), max_depth.map(|depth| depth as i32).into()],
+    ))
+    .all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.id, row.depth as u32))
+        .collect())
+}
+
+/// 逐层查询子树，每层一次查询
+async fn subtree_rows_iterative(
+    db: &DbConn,
+    root_id: Uuid,
+    max_depth: Option<u32>,
+) -> Result<Vec<(Uuid, u32)>, StoreError> {
+    if Scene::find_by_id(root_id).one(db).await?.is_none() {
+        return Ok(Vec::new());
+    }
+    let mut rows = vec![(root_id, 0)];
+    let mut level = vec![root_id];
+    let mut depth = 0;
+    while !level.is_empty() && max_depth.is_none_or(|max_depth| depth < max_depth) {
+        depth += 1;
+        level = Scene::find()
+            .filter(scene::Column::ParentId.is_in(level))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|child| child.id)
+            .filter(|id| !rows.iter().any(|(row_id, _)| row_id == id))
+            .collect();
+        rows.extend(level.iter().map(|id| (*id, depth)));
+    }
+    Ok(rows)
+}
+
+async fn ancestor_ids_postgres<C: ConnectionTrait>(
+    db: &C,
+    scene_id: Uuid,
+) -> Result<Vec<Uuid>, StoreError> {
+    let sql = r#"WITH RECURSIVE ancestors AS (
+    SELECT id, parent_id, 0 AS depth, ARRAY[id] AS path FROM scene WHERE id = $1
+    UNION ALL
+    SELECT s.id, s.parent_id, a.depth + 1, a.path || s.id
+    FROM scene s
+    JOIN ancestors a ON s.id = a.parent_id
+    WHERE NOT s.id = ANY(a.path)
+)
+SELECT id, depth FROM ancestors ORDER BY depth"#;
+    let rows = TreeRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        [scene_id.into()],
+    ))
+    .all(db)
+    .await?;
+    Ok(rows.into_iter().map(|row| row.id).collect())
+}
+
+async fn ancestor_ids_iterative<C: ConnectionTrait>(
+    db: &C,
+    scene_id: Uuid,
+) -> Result<Vec<Uuid>, StoreError> {
+    let mut ids = Vec::new();
+    let mut next = Some(scene_id);
+    while let Some(id) = next {
+        // 防止错误数据形成环导致死循环
+        if ids.contains(&id) {
+            break;
+        }
+        let Some(scene) = Scene::find_by_id(id).one(db).await? else {
+            break;
+        };
+        ids.push(id);
+        next = scene.parent_id;
+    }
+    Ok(ids)
+}
+
+async fn load_scenes<C: ConnectionTrait>(
+    db: &C,
+    ids: impl IntoIterator<Item = Uuid>,
+) -> Result<HashMap<Uuid, scene::Model>, StoreError> {
+    Ok(Scene::find()
+        .filter(scene::Column::Id.is_in(ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|scene| (scene.id, scene))
+        .collect())
+}
@@ -2,6 +2,7 @@ use crate::StoreError;
 use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use nihility_store_entity::prelude::User;
 use nihility_store_entity::user;
+pub use nihility_store_entity::sea_orm_active_enums::UserRole;
 use sea_orm::{ColumnTrait, DbConn, EntityTrait, QueryFilter};
 
 pub async fn check_user_password(
@@ -24,3 +25,15 @@ pub async fn check_user_password(
         }
     }
 }
+
+pub async fn find_user_by_name(db: &DbConn, username: &str) -> Result<user::Model, StoreError> {
+    User::find()
+        .filter(user::Column::Name.eq(username))
+        .one(db)
+        .await?
+        .ok_or_else(|| StoreError::NotFound(format!("user: {}", username)))
+}
+
+pub async fn find_user_role(db: &DbConn, username: &str) -> Result<UserRole, StoreError> {
+    Ok(find_user_by_name(db, username).await?.role)
+}
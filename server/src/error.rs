@@ -1,6 +1,9 @@
 use axum::http;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use nihility_module_manager::error::ModuleManagerError;
+use nihility_module_message_pool::MessagePoolError;
+use nihility_module_scene_manager::error::SceneError;
 use nihility_store_operate::StoreError;
 use tracing::error;
 
@@ -16,6 +19,10 @@ pub enum NihilityServerError {
     MissingCredentials,
     #[error("Wrong Credentials")]
     WrongCredentials,
+    #[error("Permission Denied: {0}")]
+    PermissionDenied(String),
+    #[error("Invalid Param: {0}")]
+    InvalidParam(String),
     #[error("Invalid Config: {0}")]
     Config(String),
     #[error(transparent)]
@@ -31,7 +38,11 @@ pub enum NihilityServerError {
     #[error(transparent)]
     InvalidHeaderValue(#[from] http::header::InvalidHeaderValue),
     #[error(transparent)]
-    ModuleManager(#[from] nihility_module_manager::error::ModuleManagerError),
+    ModuleManager(ModuleManagerError),
+    #[error(transparent)]
+    MessagePool(MessagePoolError),
+    #[error(transparent)]
+    SceneManager(SceneError),
     #[error(transparent)]
     ConfigError(#[from] nihility_config::ConfigError),
 }
@@ -41,11 +52,53 @@ impl From<StoreError> for NihilityServerError {
         match err {
             StoreError::Database(db_err) => NihilityServerError::Db(db_err),
             StoreError::NotFound(msg) => NihilityServerError::NotFound(msg),
+            StoreError::InvalidOperation(msg) => NihilityServerError::InvalidParam(msg),
             StoreError::PasswordHash(e) => NihilityServerError::PasswordHash(e.to_string()),
         }
     }
 }
 
+impl From<ModuleManagerError> for NihilityServerError {
+    fn from(err: ModuleManagerError) -> Self {
+        match err {
+            ModuleManagerError::PermissionDenied(func_name) => {
+                NihilityServerError::PermissionDenied(func_name)
+            }
+            err => NihilityServerError::ModuleManager(err),
+        }
+    }
+}
+
+impl From<MessagePoolError> for NihilityServerError {
+    fn from(err: MessagePoolError) -> Self {
+        match err {
+            MessagePoolError::Store(err) => err.into(),
+            MessagePoolError::SceneNotFound(id) => {
+                NihilityServerError::NotFound(format!("scene: {}", id))
+            }
+            MessagePoolError::MessageNotFound(id) => {
+                NihilityServerError::NotFound(format!("message: {}", id))
+            }
+            MessagePoolError::InvalidParam(desc) => NihilityServerError::InvalidParam(desc),
+            err => NihilityServerError::MessagePool(err),
+        }
+    }
+}
+
+impl From<SceneError> for NihilityServerError {
+    fn from(err: SceneError) -> Self {
+        match err {
+            SceneError::Database(err) => err.into(),
+            SceneError::InvalidMetadata(desc)
+            | SceneError::InvalidBundle(desc)
+            | SceneError::Conflict(desc) => NihilityServerError::InvalidParam(desc),
+            SceneError::Json(e) => NihilityServerError::InvalidParam(e.to_string()),
+            SceneError::TomlDeserialize(e) => NihilityServerError::InvalidParam(e.to_string()),
+            err => NihilityServerError::SceneManager(err),
+        }
+    }
+}
+
 impl IntoResponse for NihilityServerError {
     fn into_response(self) -> Response {
         match self {
@@ -63,6 +116,14 @@ impl IntoResponse for NihilityServerError {
             NihilityServerError::WrongCredentials => {
                 (StatusCode::UNAUTHORIZED, "Wrong credentials".to_string())
             }
+            NihilityServerError::PermissionDenied(func_name) => {
+                let err_msg = format!("Permission Denied: {}", func_name);
+                error!("{}", err_msg);
+                (StatusCode::FORBIDDEN, err_msg)
+            }
+            NihilityServerError::InvalidParam(desc) => {
+                (StatusCode::BAD_REQUEST, format!("Invalid Param: {}", desc))
+            }
             NihilityServerError::Config(desc) => {
                 error!("Invalid config: {}", desc);
                 (
@@ -113,6 +174,20 @@ impl IntoResponse for NihilityServerError {
                     "Module Manager Error".to_string(),
                 )
             }
+            NihilityServerError::MessagePool(e) => {
+                error!("Message Pool Error: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Message Pool Error".to_string(),
+                )
+            }
+            NihilityServerError::SceneManager(e) => {
+                error!("Scene Manager Error: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Scene Manager Error".to_string(),
+                )
+            }
             NihilityServerError::ConfigError(e) => {
                 error!("Config Error: {}", e);
                 (
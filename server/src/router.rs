@@ -2,8 +2,10 @@ mod embed_assets;
 mod html_page;
 mod html_page_manager;
 mod jwt;
+mod message;
 mod module_config;
 mod module_manager;
+mod scene;
 mod test;
 mod ws;
 
@@ -12,8 +14,10 @@ use crate::router::embed_assets::embed_assets_handler;
 use crate::router::html_page::get_html_page;
 use crate::router::html_page_manager::html_page_manager_router;
 use crate::router::jwt::{auth_middleware, authorize};
+use crate::router::message::message_router;
 use crate::router::module_config::module_config_router;
 use crate::router::module_manager::module_manager_router;
+use crate::router::scene::scene_router;
 use crate::router::test::test;
 use crate::router::ws::ws_router;
 use crate::AppState;
@@ -31,6 +35,8 @@ pub(super) fn app_router(state: AppState) -> Router<AppState> {
                 .nest("/modules", module_manager_router())
                 .nest("/module-configs", module_config_router())
                 .nest("/html-pages", html_page_manager_router())
+                .nest("/messages", message_router())
+                .nest("/scenes", scene_router())
                 .fallback(any(not_found))
                 .layer(middleware::from_fn_with_state(
                     state.jwt.clone(),
@@ -26,9 +26,7 @@ pub async fn authorize(
     if payload.username.is_empty() || payload.password.is_empty() {
         return Err(NihilityServerError::MissingCredentials);
     }
-    if !user::check_user_password(&state.conn, payload.username.clone(), payload.password)
-        .await?
-    {
+    if !user::check_user_password(&state.conn, payload.username.clone(), payload.password).await? {
         return Err(NihilityServerError::WrongCredentials);
     }
 
@@ -54,22 +52,32 @@ pub(crate) async fn auth_middleware(
         .strip_prefix("Bearer ")
         .ok_or(NihilityServerError::InvalidToken)?;
 
-    let token_data = decode::<Claims>(bearer, &keys.decoding, &Validation::default())
-        .map_err(|_| NihilityServerError::InvalidToken)?;
+    let username = verify_token(&keys, bearer)?;
 
     request
         .headers_mut()
-        .insert("x-username", HeaderValue::from_str(&token_data.claims.sub)?);
+        .insert("x-username", HeaderValue::from_str(&username)?);
 
     Ok(next.run(request).await)
 }
 
+/// 校验 token，返回其中的用户名
+pub(crate) fn verify_token(keys: &JwtKeys, token: &str) -> Result<String> {
+    let token_data = decode::<Claims>(token, &keys.decoding, &Validation::default())
+        .map_err(|_| NihilityServerError::InvalidToken)?;
+    Ok(token_data.claims.sub)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Claims {
     sub: String,
     exp: usize,
 }
 
+/// 当前请求的用户名，由 [`auth_middleware`] 写入请求头
+#[derive(Debug, Clone)]
+pub(crate) struct CurrentUser(pub(crate) String);
+
 #[derive(Debug, Serialize)]
 pub struct AuthBody {
     access_token: String,
@@ -112,6 +120,25 @@ where
     }
 }
 
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+{
+    type Rejection = NihilityServerError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> core::result::Result<Self, Self::Rejection> {
+        let username = parts
+            .headers
+            .get("x-username")
+            .and_then(|header| header.to_str().ok())
+            .ok_or(NihilityServerError::InvalidToken)?;
+        Ok(CurrentUser(username.to_string()))
+    }
+}
+
 impl JwtKeys {
     pub(crate) fn new(secret: &[u8], expiration: usize) -> Self {
         Self {
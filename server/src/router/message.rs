@@ -0,0 +1,28 @@
+use crate::AppState;
+use crate::error::*;
+use crate::router::not_found;
+use axum::extract::{Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use nihility_module_message_pool::func::query_messages::{QueryMessagesParam, QueryMessagesResult};
+
+pub fn message_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(query_messages))
+        .fallback(not_found)
+}
+
+/// 分页查询场景消息
+pub async fn query_messages(
+    State(state): State<AppState>,
+    Query(param): Query<QueryMessagesParam>,
+) -> Result<Json<QueryMessagesResult>> {
+    let result = state
+        .module_manager
+        .get_message_pool()?
+        .read()
+        .await
+        .query_messages(param)
+        .await?;
+    Ok(Json(result))
+}
@@ -1,4 +1,5 @@
 use crate::error::*;
+use crate::router::jwt::CurrentUser;
 use crate::router::not_found;
 use crate::AppState;
 use axum::extract::{Path, State};
@@ -6,17 +7,21 @@ use axum::response::sse::{Event, Sse};
 use axum::routing::{get, post};
 use axum::{Json, Router};
 use futures::StreamExt;
-use nihility_module_manager::{ModuleFunctions, ModuleType};
+use nihility_module_manager::agent::AgentResult;
+use nihility_module_manager::{CallPermission, ModuleFunctions, ModuleType};
+use nihility_store_operate::user::{self, UserRole};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::convert::Infallible;
 use std::pin::Pin;
+use uuid::Uuid;
 
 pub fn module_manager_router() -> Router<AppState> {
     Router::new()
         .route("/", get(get_loaded_modules))
         .route("/functions", get(query_all_functions))
+        .route("/agent", post(run_agent))
         .route("/{module_type}/functions", get(query_module_functions))
         .route("/{module_type}/call", post(call_module_function))
         .route("/{module_type}/stream", post(stream_module_function))
@@ -63,21 +68,50 @@ pub struct CallResponse {
     pub result: Value,
 }
 
+/// 智能体运行请求
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AgentRequest {
+    pub group_id: Uuid,
+}
+
+/// 以消息组为对话历史运行智能体，仅管理员可用
+pub async fn run_agent(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+    Json(request): Json<AgentRequest>,
+) -> Result<Json<AgentResult>> {
+    if let CallPermission::NoPerm = caller_permission(&state, &current_user).await? {
+        return Err(NihilityServerError::PermissionDenied("agent".to_string()));
+    }
+    let result = state.module_manager.run_agent(request.group_id).await?;
+    Ok(Json(result))
+}
+
+/// 根据用户角色获取调用权限
+pub(crate) async fn caller_permission(state: &AppState, current_user: &CurrentUser) -> Result<CallPermission> {
+    Ok(match user::find_user_role(&state.conn, &current_user.0).await? {
+        UserRole::Admin => CallPermission::Perm,
+        UserRole::User => CallPermission::NoPerm,
+    })
+}
+
 /// 调用指定模块的方法
 pub async fn call_module_function(
     State(state): State<AppState>,
+    current_user: CurrentUser,
     Path(module_type): Path<ModuleType>,
     Json(request): Json<CallRequest>,
 ) -> Result<Json<CallResponse>> {
+    let permission = caller_permission(&state, &current_user).await?;
     let result = if request.is_mut {
         state
             .module_manager
-            .call_mut(&module_type, &request.func_name, request.param)
+            .call_mut(&module_type, &request.func_name, request.param, permission)
             .await?
     } else {
         state
             .module_manager
-            .call(&module_type, &request.func_name, request.param)
+            .call(&module_type, &request.func_name, request.param, permission)
             .await?
     };
 
@@ -87,13 +121,18 @@ pub async fn call_module_function(
 /// 流式调用指定模块的方法 (SSE)
 pub async fn stream_module_function(
     State(state): State<AppState>,
+    current_user: CurrentUser,
     Path(module_type): Path<ModuleType>,
     Json(request): Json<CallRequest>,
 ) -> Sse<impl futures::Stream<Item = std::result::Result<Event, Infallible>>> {
-    let stream_result = state
-        .module_manager
-        .stream_call(&module_type, &request.func_name, request.param)
-        .await;
+    let stream_result = match caller_permission(&state, &current_user).await {
+        Ok(permission) => state
+            .module_manager
+            .stream_call(&module_type, &request.func_name, request.param, permission)
+            .await
+            .map_err(NihilityServerError::from),
+        Err(e) => Err(e),
+    };
 
     type StreamType = Pin<Box<dyn futures::Stream<Item = std::result::Result<Event, Infallible>> + Send>>;
 
@@ -0,0 +1,64 @@
+use crate::AppState;
+use crate::error::*;
+use crate::router::jwt::CurrentUser;
+use crate::router::module_manager::caller_permission;
+use crate::router::not_found;
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
+use nihility_module_manager::CallPermission;
+use nihility_module_scene_manager::func::export_scene_bundle::{
+    ExportSceneBundleParam, ExportSceneBundleResult,
+};
+use nihility_module_scene_manager::func::import_scene_bundle::{
+    ImportSceneBundleParam, ImportSceneBundleResult,
+};
+
+pub fn scene_router() -> Router<AppState> {
+    Router::new()
+        .route("/export", post(export_scene_bundle))
+        .route("/import", post(import_scene_bundle))
+        .fallback(not_found)
+}
+
+/// 导出场景子树为场景包，仅管理员可用
+pub async fn export_scene_bundle(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+    Json(param): Json<ExportSceneBundleParam>,
+) -> Result<Json<ExportSceneBundleResult>> {
+    if let CallPermission::NoPerm = caller_permission(&state, &current_user).await? {
+        return Err(NihilityServerError::PermissionDenied(
+            "export_scene_bundle".to_string(),
+        ));
+    }
+    let result = state
+        .module_manager
+        .get_scene_manager()?
+        .read()
+        .await
+        .export_scene_bundle(param)
+        .await?;
+    Ok(Json(result))
+}
+
+/// 导入场景包，仅管理员可用
+pub async fn import_scene_bundle(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+    Json(param): Json<ImportSceneBundleParam>,
+) -> Result<Json<ImportSceneBundleResult>> {
+    if let CallPermission::NoPerm = caller_permission(&state, &current_user).await? {
+        return Err(NihilityServerError::PermissionDenied(
+            "import_scene_bundle".to_string(),
+        ));
+    }
+    let result = state
+        .module_manager
+        .get_scene_manager()?
+        .write()
+        .await
+        .import_scene_bundle(param)
+        .await?;
+    Ok(Json(result))
+}
@@ -1,15 +1,27 @@
 use crate::error::*;
+use crate::router::jwt::verify_token;
 use crate::router::not_found;
 use crate::AppState;
-use axum::extract::{State, WebSocketUpgrade};
+use axum::extract::ws::{Message, WebSocket};
+use axum::extract::{Path, Query, State, WebSocketUpgrade};
+use axum::http::{HeaderMap, header};
 use axum::response::Response;
 use axum::routing::any;
 use axum::Router;
-use tracing::{debug, error};
+use nihility_module_message_pool::SceneEvent;
+use nihility_store_operate::scene;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use std::collections::HashSet;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, warn};
+use uuid::Uuid;
 
 pub fn ws_router() -> Router<AppState> {
     Router::new()
         .route("/edge-device-control", any(edge_device_control))
+        .route("/scenes/{id}", any(scene_events))
         .fallback(not_found)
 }
 
@@ -33,3 +45,121 @@ async fn edge_device_control(
         }
     }))
 }
+
+/// 场景订阅参数
+#[derive(Debug, Deserialize)]
+struct SceneEventsQuery {
+    /// 是否同时订阅全部子场景（默认 false）
+    #[serde(default)]
+    subtree: bool,
+    /// 访问 token，浏览器无法为 WebSocket 设置请求头时使用
+    token: Option<String>,
+}
+
+/// 订阅场景的实时事件，事件以 JSON 文本帧推送
+async fn scene_events(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Path(scene_id): Path<Uuid>,
+    Query(query): Query<SceneEventsQuery>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .map(String::from)
+        .or(query.token)
+        .ok_or(NihilityServerError::InvalidToken)?;
+    let username = verify_token(&state.jwt, &token)?;
+    debug!("WS scene {} events subscribed by {}", scene_id, username);
+
+    let scene_ids = if query.subtree {
+        scene::find_scene_subtree_ids(&state.conn, scene_id).await?
+    } else {
+        vec![scene::find_scene_by_id(&state.conn, scene_id).await?.id]
+    };
+    let event_receiver = state
+        .module_manager
+        .get_message_pool()?
+        .read()
+        .await
+        .subscribe_events();
+    let filter = SceneFilter {
+        conn: state.conn.clone(),
+        root_id: scene_id,
+        subtree: query.subtree,
+        included: scene_ids.into_iter().collect(),
+        excluded: HashSet::new(),
+    };
+    Ok(ws.on_upgrade(move |socket| forward_scene_events(socket, event_receiver, filter)))
+}
+
+/// 订阅的场景范围
+struct SceneFilter {
+    conn: DatabaseConnection,
+    root_id: Uuid,
+    subtree: bool,
+    included: HashSet<Uuid>,
+    excluded: HashSet<Uuid>,
+}
+
+impl SceneFilter {
+    async fn contains(&mut self, scene_id: Uuid) -> bool {
+        if self.included.contains(&scene_id) {
+            return true;
+        }
+        if !self.subtree || self.excluded.contains(&scene_id) {
+            return false;
+        }
+        // 订阅后新建的子场景需要沿祖先链确认
+        let in_subtree = scene::find_scene_ancestors(&self.conn, scene_id)
+            .await
+            .is_ok_and(|ancestors| ancestors.iter().any(|scene| scene.id == self.root_id));
+        if in_subtree {
+            self.included.insert(scene_id);
+        } else {
+            self.excluded.insert(scene_id);
+        }
+        in_subtree
+    }
+}
+
+async fn forward_scene_events(
+    mut socket: WebSocket,
+    mut event_receiver: broadcast::Receiver<SceneEvent>,
+    mut filter: SceneFilter,
+) {
+    loop {
+        tokio::select! {
+            event = event_receiver.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(count)) => {
+                        warn!("Scene {} subscriber lagged, {} events dropped", filter.root_id, count);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if !filter.contains(event.scene_id()).await {
+                    continue;
+                }
+                let text = match serde_json::to_string(&event) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        error!("Serialize scene event failed: {}", e);
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    debug!("WS scene {} events unsubscribed", filter.root_id);
+}